@@ -0,0 +1,99 @@
+use crate::cube::{ Color, Cube, BACK, DOWN, FRONT, LEFT, RIGHT, UP };
+use crate::scheme::ColorScheme;
+use rubiks_2000::cube::cube::Cube as CubieCube;
+use rubiks_2000::cube::enums::{ Color as Face, CubeError };
+use rubiks_2000::cube::face_cube::FaceCube;
+use std::fmt;
+
+/// The index of the face of the NxN cube for each face of the solver (U, R, F, D, L, B).
+/// Both models draw the faces on the same net, so the stickers of a face are in the same order.
+pub const FACE_INDEX: [usize; 6] = [UP, RIGHT, FRONT, DOWN, LEFT, BACK];
+
+/// The reasons why a NxN cube can not be converted for the solver
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConvertError {
+    /// The solver only knows the 3x3x3 cube
+    Size(usize),
+    /// A sticker has a color which is not in the color scheme (or the Empty color)
+    UnknownColor(Color),
+    /// The centers are not in the positions given by the color scheme
+    Centers,
+    /// Some stickers do not form a real cubie
+    Stickers,
+    /// The stickers do not form a solvable cube
+    Illegal(CubeError),
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConvertError::Size(size) => write!(f, "The solver needs a 3x3x3 cube, not a {0}x{0}x{0}", size),
+            ConvertError::UnknownColor(color) => write!(f, "The color {} is not in the color scheme", color),
+            ConvertError::Centers => write!(f, "The centers do not match the color scheme"),
+            ConvertError::Stickers => write!(f, "Some stickers do not form a real cubie"),
+            ConvertError::Illegal(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ConvertError {}
+
+/// This function convert a facelet cube of the solver to a 3x3x3 cube
+///
+/// # Args
+/// * 'cube' - the cube of the solver
+/// * 'scheme' - the color of each face of the solver
+pub fn to_nxn(cube: &FaceCube, scheme: &ColorScheme) -> Cube {
+    let mut nxn = Cube::new_filled(3);
+    for (face, index) in FACE_INDEX.iter().enumerate() {
+        for sticker in 0..9 {
+            let color = scheme.color(cube.get_facelet(9 * face + sticker));
+            nxn.set_sticker(*index, sticker / 3, sticker % 3, color);
+        }
+    }
+    nxn
+}
+
+/// This function convert a 3x3x3 cube to a facelet cube of the solver.
+/// Nothing ensures that the facelet cube is solvable, see to_cubie_cube.
+///
+/// # Args
+/// * 'cube' - the 3x3x3 cube
+/// * 'scheme' - the color of each face of the solver
+pub fn to_face_cube(cube: &Cube, scheme: &ColorScheme) -> Result<FaceCube, ConvertError> {
+    if cube.get_size() != 3 {
+        return Err(ConvertError::Size(cube.get_size()));
+    }
+    let mut face_cube = FaceCube::new();
+    for (face, index) in FACE_INDEX.iter().enumerate() {
+        for sticker in 0..9 {
+            let color = cube.get_sticker(*index, sticker / 3, sticker % 3);
+            let facelet: Face = scheme.face(color).ok_or(ConvertError::UnknownColor(color))?;
+            face_cube.set_facelet(9 * face + sticker, facelet);
+        }
+    }
+    Ok(face_cube)
+}
+
+/// This function convert a cubie cube of the solver to a 3x3x3 cube
+pub fn cubie_to_nxn(cube: &CubieCube, scheme: &ColorScheme) -> Cube {
+    to_nxn(&cube.to_facelet_cube(), scheme)
+}
+
+/// This function convert a 3x3x3 cube to a cubie cube of the solver, checking that it is solvable
+pub fn to_cubie_cube(cube: &Cube, scheme: &ColorScheme) -> Result<CubieCube, ConvertError> {
+    let face_cube = to_face_cube(cube, scheme)?;
+    // The centers must be in the positions of the scheme
+    for face in 0..6 {
+        if face_cube.get_facelet(9 * face + 4) != Face::from(face) {
+            return Err(ConvertError::Centers);
+        }
+    }
+    // FaceCube::to_cubie_cube ignores the stickers which do not form a cubie
+    let cubie_cube = face_cube.to_cubie_cube();
+    if cubie_cube.to_facelet_cube() != face_cube {
+        return Err(ConvertError::Stickers);
+    }
+    cubie_cube.check().map_err(ConvertError::Illegal)?;
+    Ok(cubie_cube)
+}
@@ -1,8 +1,10 @@
+use crate::scheme::ColorScheme;
 use rand::Rng;
+use rubiks_2000::render::theme::Theme;
 use std::fmt;
 use std::vec;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Color {
     Red,
     Orange,
@@ -14,15 +16,11 @@ pub enum Color {
 }
 
 impl Color {
-    fn pretty_print(&self) {
-        let code = match self {
-            Self::Blue => "\x1b[34m",
-            Self::White => "\x1b[37m",
-            Self::Green => "\x1b[32m",
-            Self::Red => "\x1b[31m",
-            Self::Orange => "\x1b[38;5;208m",
-            Self::Yellow => "\x1b[33m",
-            _ => "\x1b[30m",
+    fn pretty_print(&self, theme: &Theme) {
+        // The colors of the Cube are the faces of Cube::new_filled
+        let code = match ColorScheme::DELTA.rgb(theme, *self) {
+            Some(rgb) => rgb.ansi(),
+            None => String::from("\x1b[30m"),
         };
         let reset_code = "\x1b[0m"; // Code pour réinitialiser la couleur à la couleur par défaut
         print!("{}██{}", code, reset_code);
@@ -44,7 +42,17 @@ impl fmt::Display for Color {
     }
 }
 
-#[derive(Clone, Debug)]
+/// The index of each face in the cube. The faces are drawn on the same net as the solver facelets
+/// (see the dbg function): the up face above the front one, the down face below it, and the left, front,
+/// right and back faces from left to right.
+pub const FRONT: usize = 0;
+pub const UP: usize = 1;
+pub const LEFT: usize = 2;
+pub const DOWN: usize = 3;
+pub const RIGHT: usize = 4;
+pub const BACK: usize = 5;
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct Cube {
     size: usize,
     faces: Vec<Vec<Color>>,
@@ -58,7 +66,7 @@ impl Cube {
     pub fn new_empty(_size: usize) -> Self {
         Cube {
             size: _size,
-            faces: vec![vec![Color::Empty; _size * _size]; _size],
+            faces: vec![vec![Color::Empty; _size * _size]; 6],
         }
     }
 
@@ -91,8 +99,32 @@ impl Cube {
         self.size
     }
 
+    /// This function return the color of a sticker
+    ///
+    /// # Args
+    /// * 'face' - the index of the face (see FRONT, UP...)
+    /// * 'row, col' - the position of the sticker on the face, as drawn by dbg
+    pub fn get_sticker(&self, face: usize, row: usize, col: usize) -> Color {
+        self.faces[face][row * self.size + col]
+    }
+
+    /// This function change the color of a sticker
+    ///
+    /// # Args
+    /// * 'face' - the index of the face (see FRONT, UP...)
+    /// * 'row, col' - the position of the sticker on the face, as drawn by dbg
+    /// * 'color' - the new color
+    pub fn set_sticker(&mut self, face: usize, row: usize, col: usize, color: Color) {
+        self.faces[face][row * self.size + col] = color;
+    }
+
     /// This function draw the cube in the console
     pub fn dbg(&self) {
+        self.dbg_with_theme(&Theme::DELTA);
+    }
+
+    /// This function draw the cube in the console with the colors of a theme
+    pub fn dbg_with_theme(&self, theme: &Theme) {
         println!();
         for (j, case) in self.faces[1].iter().enumerate() {
             if j % self.size == 0 {
@@ -100,14 +132,14 @@ impl Cube {
                 let space = " ".repeat(self.size * 2);
                 print!("{}", space);
             }
-            case.pretty_print();
+            case.pretty_print(theme);
         }
         println!();
 
         for j in 0..self.size {
             for f in [2, 0, 4, 5] {
                 for i in 0..self.size {
-                    self.faces[f][i + j * self.size].pretty_print();
+                    self.faces[f][i + j * self.size].pretty_print(theme);
                 }
             }
             println!();
@@ -120,7 +152,7 @@ impl Cube {
                 let space = " ".repeat(self.size * 2);
                 print!("{}", space);
             }
-            case.pretty_print();
+            case.pretty_print(theme);
         }
 
         println!();
@@ -0,0 +1,230 @@
+use crate::convert::FACE_INDEX;
+use crate::cube::{ Color, Cube };
+use crate::moves::SliceMove;
+use crate::notation::{ BigMove, NotationError };
+use crate::scheme::ColorScheme;
+use rand::Rng;
+use rubiks_2000::cube::enums::Color as Face;
+
+// A flat layout of the NxN cube: the stickers are in one vector, face by face (FRONT, UP...) and row by row,
+// as in the Cube. A move is a permutation of this vector, computed once for each slice of a size (MoveTable),
+// so playing a move on a big cube is a single pass over the stickers.
+
+/// The permutation of the stickers by a move: after the move, the sticker i has the color the sticker perm[i]
+/// had before
+pub type Perm = Vec<usize>;
+
+/// The slice moves, in the order of MoveTable
+pub const SLICE_MOVES: [SliceMove; 6] = [SliceMove::V, SliceMove::Vp, SliceMove::H, SliceMove::Hp, SliceMove::L, SliceMove::Lp];
+
+/// The colors used to read the permutations of the moves, see slice_perm
+const DIGITS: [Color; 6] = [Color::Red, Color::Orange, Color::Blue, Color::Yellow, Color::Green, Color::White];
+
+/// This function return the permutation of a slice move, read on the Cube: the stickers are colored with
+/// the digits of their index in base 6, and the digits are read back after the move
+pub fn slice_perm(mov: SliceMove, n: usize, size: usize) -> Perm {
+    let count = 6 * size * size;
+    let mut perm = vec![0; count];
+    let mut power = 1;
+    while power < count {
+        let mut cube = Cube::new_filled(size);
+        for i in 0..count {
+            cube.set_sticker(i / (size * size), (i / size) % size, i % size, DIGITS[(i / power) % 6]);
+        }
+        cube.apply_slice_move(mov, n);
+        for (i, source) in perm.iter_mut().enumerate() {
+            let color = cube.get_sticker(i / (size * size), (i / size) % size, i % size);
+            *source += power * DIGITS.iter().position(|digit| *digit == color).unwrap();
+        }
+        power *= 6;
+    }
+    perm
+}
+
+/// This function return the permutation of a permutation followed by another one
+pub fn compose(first: &Perm, second: &Perm) -> Perm {
+    second.iter().map(|i| first[*i]).collect()
+}
+
+/// This function return the permutation which undoes a permutation
+pub fn invert(perm: &Perm) -> Perm {
+    let mut inverse = vec![0; perm.len()];
+    for (i, source) in perm.iter().enumerate() {
+        inverse[*source] = i;
+    }
+    inverse
+}
+
+/// The permutations of the slice moves of a size of cube
+#[derive(Clone, Debug)]
+pub struct MoveTable {
+    size: usize,
+    /// The permutation of each slice move, indexed by move (see SLICE_MOVES) then by slice
+    perms: Vec<Vec<Perm>>,
+}
+
+impl MoveTable {
+    /// This function compute the permutations of the moves of a cube of size 'size'
+    pub fn new(size: usize) -> Self {
+        let perms = SLICE_MOVES
+            .iter()
+            .map(|mov| (0..size).map(|n| slice_perm(*mov, n, size)).collect())
+            .collect();
+        MoveTable { size, perms }
+    }
+
+    /// This function return the size of the cube
+    pub fn get_size(&self) -> usize {
+        self.size
+    }
+
+    /// This function return the number of stickers of the cube
+    pub fn len(&self) -> usize {
+        6 * self.size * self.size
+    }
+
+    /// This function return true for the cube of size 0
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// This function return the permutation of a slice move
+    ///
+    /// # Args
+    /// * 'mov' - the type of move
+    /// * 'n' - the slice concerned by the move, numbered as in the Cube
+    pub fn slice(&self, mov: SliceMove, n: usize) -> &Perm {
+        &self.perms[SLICE_MOVES.iter().position(|m| *m == mov).unwrap()][n]
+    }
+
+    /// This function return the permutations of all the slice quarter turns
+    pub fn slices(&self) -> impl Iterator<Item = &Perm> {
+        self.perms.iter().flatten()
+    }
+
+    /// This function return the stickers of each piece: the stickers of the border of the faces which the same
+    /// slices move are a corner or an edge, the other stickers are centers of one sticker (a face turn can leave
+    /// a center in place)
+    pub fn pieces(&self) -> Vec<Vec<usize>> {
+        let size = self.size;
+        let mut pieces: Vec<(Vec<bool>, Vec<usize>)> = Vec::new();
+        for sticker in 0..self.len() {
+            let (row, col) = ((sticker / size) % size, sticker % size);
+            if ![row, col].iter().any(|i| *i == 0 || *i == size - 1) {
+                pieces.push((Vec::new(), vec![sticker]));
+                continue;
+            }
+            let moved_by: Vec<bool> = self.slices().map(|perm| perm[sticker] != sticker).collect();
+            match pieces.iter_mut().find(|(signature, _)| !signature.is_empty() && *signature == moved_by) {
+                Some((_, stickers)) => stickers.push(sticker),
+                None => pieces.push((moved_by, vec![sticker])),
+            }
+        }
+        pieces.into_iter().map(|(_, stickers)| stickers).collect()
+    }
+
+    /// This function return the permutation of a move of the big cube notation
+    pub fn big_move(&self, mv: BigMove) -> Result<Perm, NotationError> {
+        let identity: Perm = (0..self.len()).collect();
+        Ok(mv.to_slices(self.size)?.into_iter().fold(identity, |perm, (mov, n)| compose(&perm, self.slice(mov, n))))
+    }
+}
+
+/// A NxN cube stored as a flat vector of stickers, see the header of this file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FlatCube {
+    size: usize,
+    stickers: Vec<Color>,
+}
+
+impl FlatCube {
+    /// This function create a solved cube with the colors of a color scheme
+    pub fn new_solved(size: usize, scheme: &ColorScheme) -> Self {
+        let mut colors = [Color::Empty; 6];
+        for (face, index) in FACE_INDEX.iter().enumerate() {
+            colors[*index] = scheme.color(Face::from(face));
+        }
+        FlatCube { size, stickers: colors.iter().flat_map(|color| vec![*color; size * size]).collect() }
+    }
+
+    /// This function return the size of the cube
+    pub fn get_size(&self) -> usize {
+        self.size
+    }
+
+    /// This function return the colors of the stickers, in the order of the header of this file
+    pub fn stickers(&self) -> &[Color] {
+        &self.stickers
+    }
+
+    /// This function return the index of a sticker
+    ///
+    /// # Args
+    /// * 'face' - the index of the face (see FRONT, UP...)
+    /// * 'row, col' - the position of the sticker on the face, as in the Cube
+    pub fn index(&self, face: usize, row: usize, col: usize) -> usize {
+        (face * self.size + row) * self.size + col
+    }
+
+    /// This function return the color of a sticker
+    pub fn get_sticker(&self, face: usize, row: usize, col: usize) -> Color {
+        self.stickers[self.index(face, row, col)]
+    }
+
+    /// This function apply a permutation of the stickers (a move of the MoveTable of the size of the cube)
+    pub fn apply(&mut self, perm: &Perm) {
+        self.stickers = perm.iter().map(|i| self.stickers[*i]).collect();
+    }
+
+    /// This function apply a slice move to the cube
+    pub fn apply_slice_move(&mut self, table: &MoveTable, mov: SliceMove, n: usize) {
+        self.apply(table.slice(mov, n));
+    }
+
+    /// This function apply a move of the big cube notation to the cube
+    pub fn apply_big_move(&mut self, table: &MoveTable, mv: BigMove) -> Result<(), NotationError> {
+        for (mov, n) in mv.to_slices(self.size)? {
+            self.apply(table.slice(mov, n));
+        }
+        Ok(())
+    }
+
+    /// This function shuffle the cube by doing some random slice moves, as Cube::shuffle
+    ///
+    /// # Args
+    /// * 'table' - the moves of the size of the cube
+    /// * 'moves' - the number of move to do
+    pub fn shuffle(&mut self, table: &MoveTable, moves: u32) {
+        let mut rng = rand::thread_rng();
+        for _ in 0..moves {
+            let mov = SLICE_MOVES[rng.gen_range(0..6)];
+            self.apply(table.slice(mov, rng.gen_range(0..self.size)));
+        }
+    }
+}
+
+impl From<&Cube> for FlatCube {
+    fn from(cube: &Cube) -> Self {
+        let size = cube.get_size();
+        let mut stickers = Vec::with_capacity(6 * size * size);
+        for face in 0..6 {
+            for row in 0..size {
+                for col in 0..size {
+                    stickers.push(cube.get_sticker(face, row, col));
+                }
+            }
+        }
+        FlatCube { size, stickers }
+    }
+}
+
+impl From<&FlatCube> for Cube {
+    fn from(flat: &FlatCube) -> Self {
+        let size = flat.size;
+        let mut cube = Cube::new_empty(size);
+        for (i, color) in flat.stickers.iter().enumerate() {
+            cube.set_sticker(i / (size * size), (i / size) % size, i % size, *color);
+        }
+        cube
+    }
+}
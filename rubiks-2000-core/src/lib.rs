@@ -0,0 +1,13 @@
+pub mod convert;
+pub mod cube;
+pub mod flat;
+pub mod moves;
+pub mod notation;
+pub mod reduction;
+pub mod scheme;
+pub mod scramble;
+pub mod validate;
+
+// Test module
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,96 @@
+use crate::cube::Cube;
+use rubiks_2000::cube::enums::Move;
+
+/// The moves of a slice of the NxN cube (the same as the moves of the 3D cube, gcube::Move).
+/// The slice is given by an index, from the left for V, from the top for H, from the back for L.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SliceMove {
+    /// Vertical slice upward (the right face turns like R, the left one like L')
+    V,
+    Vp,
+    /// Horizontal slice to the right (the up face turns like U', the down one like D)
+    H,
+    Hp,
+    /// Lateral slice to the right (the front face turns like F, the back one like B')
+    L,
+    Lp,
+}
+
+impl SliceMove {
+    /// This function return the inverse move
+    pub fn inverse(self) -> Self {
+        match self {
+            SliceMove::V => SliceMove::Vp,
+            SliceMove::Vp => SliceMove::V,
+            SliceMove::H => SliceMove::Hp,
+            SliceMove::Hp => SliceMove::H,
+            SliceMove::L => SliceMove::Lp,
+            SliceMove::Lp => SliceMove::L,
+        }
+    }
+}
+
+impl Cube {
+    /// This function apply a slice move to the cube
+    ///
+    /// # Args
+    /// * 'mov' - the type of move
+    /// * 'n' - the slice concerned by the move
+    pub fn apply_slice_move(&mut self, mov: SliceMove, n: usize) {
+        match mov {
+            SliceMove::V => self.move_v(n),
+            SliceMove::Vp => self.move_vp(n),
+            SliceMove::H => self.move_h(n),
+            SliceMove::Hp => self.move_hp(n),
+            SliceMove::L => self.move_l(n),
+            SliceMove::Lp => self.move_lp(n),
+        }
+    }
+}
+
+/// This function return the slice moves of a face move of the solver on a cube of size 'size'
+/// (a half turn gives two slice moves)
+pub fn face_move_to_slices(mv: Move, size: usize) -> Vec<(SliceMove, usize)> {
+    let last = size - 1;
+    // The quarter turn clockwise of the face
+    let quarter = match mv {
+        Move::U1 | Move::U2 | Move::U3 => (SliceMove::Hp, 0),
+        Move::D1 | Move::D2 | Move::D3 => (SliceMove::H, last),
+        Move::R1 | Move::R2 | Move::R3 => (SliceMove::V, last),
+        Move::L1 | Move::L2 | Move::L3 => (SliceMove::Vp, 0),
+        Move::F1 | Move::F2 | Move::F3 => (SliceMove::L, last),
+        Move::B1 | Move::B2 | Move::B3 => (SliceMove::Lp, 0),
+        Move::Default => return Vec::new(),
+    };
+    match mv {
+        Move::U1 | Move::D1 | Move::R1 | Move::L1 | Move::F1 | Move::B1 => vec![quarter],
+        Move::U2 | Move::D2 | Move::R2 | Move::L2 | Move::F2 | Move::B2 => vec![quarter, quarter],
+        _ => vec![(quarter.0.inverse(), quarter.1)],
+    }
+}
+
+/// This function return the slice moves of a sequence of face moves on a cube of size 'size'
+pub fn algorithm_to_slices(moves: &[Move], size: usize) -> Vec<(SliceMove, usize)> {
+    moves.iter().flat_map(|mv| face_move_to_slices(*mv, size)).collect()
+}
+
+/// This function return the face move of a slice move, None for the inner slices
+/// (which have no face move in the solver)
+pub fn slice_to_face_move(mov: SliceMove, n: usize, size: usize) -> Option<Move> {
+    let last = size - 1;
+    match (mov, n) {
+        (SliceMove::Hp, 0) => Some(Move::U1),
+        (SliceMove::H, 0) => Some(Move::U3),
+        (SliceMove::H, n) if n == last => Some(Move::D1),
+        (SliceMove::Hp, n) if n == last => Some(Move::D3),
+        (SliceMove::V, n) if n == last => Some(Move::R1),
+        (SliceMove::Vp, n) if n == last => Some(Move::R3),
+        (SliceMove::Vp, 0) => Some(Move::L1),
+        (SliceMove::V, 0) => Some(Move::L3),
+        (SliceMove::L, n) if n == last => Some(Move::F1),
+        (SliceMove::Lp, n) if n == last => Some(Move::F3),
+        (SliceMove::Lp, 0) => Some(Move::B1),
+        (SliceMove::L, 0) => Some(Move::B3),
+        _ => None,
+    }
+}
@@ -0,0 +1,242 @@
+use crate::cube::Cube;
+use crate::moves::SliceMove;
+use rubiks_2000::cube::enums::{ Color as Face, Move };
+use rubiks_2000::cube::formats::FACE_LETTERS;
+use std::fmt;
+use std::str::FromStr;
+
+// The big cube notation (WCA and SiGN) for any size:
+//
+//     R, R2, R'      the face
+//     2R             the second layer from the face only (inner slice)
+//     Rw, r          the two outer layers
+//     3Rw, 3r        the three outer layers
+//     2-4Rw, 2-4r    the layers 2 to 4 from the face
+//     M, E, S        all the inner layers, turned like L, D and F
+//     x, y, z        the whole cube, turned like R, U and F
+//
+// The layers are numbered from 1 at the face.
+
+/// The layers turned by a big cube move
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Layers {
+    /// The layers 'first' to 'last' counted from the face (both included)
+    Range(usize, usize),
+    /// All the layers but the face and its opposite face (M, E, S)
+    Inner,
+    /// All the layers (x, y, z)
+    All,
+}
+
+/// A move of the big cube notation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BigMove {
+    /// The face which gives the direction of the move (the layers turn like this face)
+    pub face: Face,
+    pub layers: Layers,
+    /// The number of quarter turns clockwise: 1, 2 or 3 (3 is written ')
+    pub turns: u8,
+}
+
+/// The reasons why a big cube move can not be read or played
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NotationError {
+    /// The move is not written in the big cube notation
+    Parse(String),
+    /// The move turns layers which are not on a cube of this size
+    Layers(String, usize),
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotationError::Parse(mv) => write!(f, "Unknown move \"{}\"", mv),
+            NotationError::Layers(mv, size) => write!(f, "The move {} does not exist on a {1}x{1}x{1} cube", mv, size),
+        }
+    }
+}
+
+impl std::error::Error for NotationError {}
+
+/// This function return the slice move of a quarter turn clockwise of a face, and the index of a layer
+/// (numbered from 1 at the face) in the slices of the Cube
+fn face_slice(face: Face, layer: usize, size: usize) -> (SliceMove, usize) {
+    match face {
+        Face::U => (SliceMove::Hp, layer - 1),
+        Face::D => (SliceMove::H, size - layer),
+        Face::R => (SliceMove::V, size - layer),
+        Face::L => (SliceMove::Vp, layer - 1),
+        Face::F => (SliceMove::L, size - layer),
+        Face::B => (SliceMove::Lp, layer - 1),
+    }
+}
+
+impl BigMove {
+    /// This function return the move of a face move of the solver
+    /// # Panics :
+    /// On Move::Default, which is not a real move.
+    pub fn from_face_move(mv: Move) -> Self {
+        assert!(mv != Move::Default, "Move::Default is not a real move");
+        BigMove {
+            face: Face::from((mv as usize) / 3),
+            layers: Layers::Range(1, 1),
+            turns: ((mv as usize) % 3) as u8 + 1,
+        }
+    }
+
+    /// This function return the quarter turn of one slice of the Cube, named from its nearest face
+    ///
+    /// # Args
+    /// * 'mov' - the type of move
+    /// * 'n' - the slice concerned by the move, numbered as in the Cube
+    /// * 'size' - the size of the cube
+    pub fn from_slice(mov: SliceMove, n: usize, size: usize) -> Self {
+        // The faces at the end of the slices numbered from 0, then from size - 1
+        let (near, far) = match mov {
+            SliceMove::V | SliceMove::Vp => (Face::L, Face::R),
+            SliceMove::H | SliceMove::Hp => (Face::U, Face::D),
+            SliceMove::L | SliceMove::Lp => (Face::B, Face::F),
+        };
+        let (face, layer) = if size - n <= n + 1 { (far, size - n) } else { (near, n + 1) };
+        let turns = if face_slice(face, layer, size).0 == mov { 1 } else { 3 };
+        BigMove { face, layers: Layers::Range(layer, layer), turns }
+    }
+
+    /// This function return the inverse move
+    pub fn inverse(self) -> Self {
+        BigMove { turns: 4 - self.turns, ..self }
+    }
+
+    /// This function return the slice moves of the move on a cube of size 'size', to play or animate it
+    /// (a half turn gives each slice move twice). A range of layers must be in 1..=size, the first layer first.
+    pub fn to_slices(self, size: usize) -> Result<Vec<(SliceMove, usize)>, NotationError> {
+        let layers = match self.layers {
+            Layers::Range(first, last) if 1 <= first && first <= last && last <= size => first..=last,
+            Layers::Range(..) => return Err(NotationError::Layers(self.to_string(), size)),
+            Layers::Inner => 2..=size - 1,
+            Layers::All => 1..=size,
+        };
+        let quarter: Vec<(SliceMove, usize)> = layers
+            .map(|layer| face_slice(self.face, layer, size))
+            .map(|(mov, n)| if self.turns == 3 { (mov.inverse(), n) } else { (mov, n) })
+            .collect();
+        Ok(quarter.repeat(if self.turns == 2 { 2 } else { 1 }))
+    }
+}
+
+impl Cube {
+    /// This function apply a move of the big cube notation to the cube
+    pub fn apply_big_move(&mut self, mv: BigMove) -> Result<(), NotationError> {
+        for (mov, n) in mv.to_slices(self.get_size())? {
+            self.apply_slice_move(mov, n);
+        }
+        Ok(())
+    }
+}
+
+/// Write the move in the big cube notation (the wide moves are written with a w, as in the WCA notation)
+impl fmt::Display for BigMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let letter = FACE_LETTERS[self.face as usize];
+        // The moves of all the layers are named from R, U and F, the ones of the inner layers from L, D and F
+        let (name, inverted) = match self.layers {
+            Layers::Range(1, 1) => (letter.to_string(), false),
+            Layers::Range(1, 2) => (format!("{}w", letter), false),
+            Layers::Range(1, last) => (format!("{}{}w", last, letter), false),
+            Layers::Range(first, last) if first == last => (format!("{}{}", first, letter), false),
+            Layers::Range(first, last) => (format!("{}-{}{}w", first, last, letter), false),
+            Layers::Inner => match self.face {
+                Face::L | Face::R => (String::from("M"), self.face == Face::R),
+                Face::D | Face::U => (String::from("E"), self.face == Face::U),
+                Face::F | Face::B => (String::from("S"), self.face == Face::B),
+            },
+            Layers::All => match self.face {
+                Face::R | Face::L => (String::from("x"), self.face == Face::L),
+                Face::U | Face::D => (String::from("y"), self.face == Face::D),
+                Face::F | Face::B => (String::from("z"), self.face == Face::B),
+            },
+        };
+        let turns = if inverted { 4 - self.turns } else { self.turns };
+        match turns {
+            1 => write!(f, "{}", name),
+            2 => write!(f, "{}2", name),
+            _ => write!(f, "{}'", name),
+        }
+    }
+}
+
+/// Parse a move of the big cube notation (see the header of this file).
+/// "R2'" is accepted as a synonym of "R2" and "R3" as a synonym of "R'".
+impl FromStr for BigMove {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || NotationError::Parse(String::from(s));
+
+        // The suffix gives the number of turns
+        let (body, turns) = [("2'", 2), ("2’", 2), ("2", 2), ("'", 3), ("’", 3), ("3", 3)]
+            .iter()
+            .find_map(|(suffix, turns)| s.strip_suffix(suffix).map(|body| (body, *turns)))
+            .unwrap_or((s, 1));
+
+        // The prefix gives the layers: "3" or "2-4"
+        let start = body.find(|c: char| !c.is_ascii_digit() && c != '-').ok_or_else(error)?;
+        let (prefix, name) = body.split_at(start);
+        let numbers = if prefix.is_empty() {
+            Vec::new()
+        } else {
+            prefix.split('-').map(|n| n.parse::<usize>().map_err(|_| error())).collect::<Result<Vec<_>, _>>()?
+        };
+        if numbers.contains(&0) {
+            return Err(error());
+        }
+
+        let mut chars = name.chars();
+        let letter = chars.next().ok_or_else(error)?;
+        let wide = match chars.as_str() {
+            "" => letter.is_ascii_lowercase(),
+            "w" if letter.is_ascii_uppercase() => true,
+            _ => return Err(error()),
+        };
+        let face = FACE_LETTERS.iter().position(|l| *l == letter.to_ascii_uppercase()).map(Face::from);
+
+        let (face, layers) = match (face, letter, wide, &numbers[..]) {
+            // The layers of a face
+            (Some(face), _, false, []) => (face, Layers::Range(1, 1)),
+            (Some(face), _, false, &[layer]) => (face, Layers::Range(layer, layer)),
+            (Some(face), _, true, []) => (face, Layers::Range(1, 2)),
+            (Some(face), _, true, &[last]) => (face, Layers::Range(1, last)),
+            (Some(face), _, true, &[first, last]) if first <= last => (face, Layers::Range(first, last)),
+            // The slices and the rotations
+            (None, 'M', _, []) => (Face::L, Layers::Inner),
+            (None, 'E', _, []) => (Face::D, Layers::Inner),
+            (None, 'S', _, []) => (Face::F, Layers::Inner),
+            (None, 'x', _, []) => (Face::R, Layers::All),
+            (None, 'y', _, []) => (Face::U, Layers::All),
+            (None, 'z', _, []) => (Face::F, Layers::All),
+            _ => return Err(error()),
+        };
+        Ok(BigMove { face, layers, turns })
+    }
+}
+
+/// Parse an algorithm written in the big cube notation, moves separated by whitespaces.
+/// # Example :
+/// parse_algorithm("Rw U2 3Rw' x") -> [Rw, U2, 3Rw', x]
+pub fn parse_algorithm(algorithm: &str) -> Result<Vec<BigMove>, NotationError> {
+    algorithm.split_whitespace().map(str::parse).collect()
+}
+
+/// Write an algorithm in the big cube notation, inverse of parse_algorithm
+pub fn algorithm_to_string(moves: &[BigMove]) -> String {
+    moves.iter().map(|mv| mv.to_string()).collect::<Vec<String>>().join(" ")
+}
+
+/// This function return the slice moves of an algorithm of the big cube notation on a cube of size 'size'
+pub fn big_algorithm_to_slices(moves: &[BigMove], size: usize) -> Result<Vec<(SliceMove, usize)>, NotationError> {
+    let mut slices = Vec::new();
+    for mv in moves {
+        slices.extend(mv.to_slices(size)?);
+    }
+    Ok(slices)
+}
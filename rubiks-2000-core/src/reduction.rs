@@ -0,0 +1,553 @@
+use crate::convert::{ to_cubie_cube, to_face_cube, ConvertError, FACE_INDEX };
+use crate::cube::{ Color, Cube, BACK, DOWN, FRONT, LEFT, RIGHT, UP };
+use crate::flat::{ compose, invert, FlatCube, MoveTable, Perm };
+use crate::notation::{ parse_algorithm, BigMove, Layers };
+use crate::scheme::ColorScheme;
+use rubiks_2000::cube::enums::{ Color as Face, CubeError, Move };
+use rubiks_2000::cube::face_cube::FaceCube;
+use std::collections::HashSet;
+use std::fmt;
+
+// The reduction method for the big cubes:
+//
+// 1. the centers are solved one sticker at a time, by short sequences or by commutators [A, B] (A an inner
+//    layer, B a face or a face behind an inner layer) behind setup moves;
+// 2. the edges are paired one wing at a time, by commutators which only move wings (A an inner layer, B an
+//    insertion X Y X' of faces) behind setup moves: the wings go with the middle edge of their slot on odd
+//    cubes, and straight to their solved slot on even cubes;
+// 3. the parities: when the last wings of an orbit are swapped (OLL parity), a parity algorithm flips an
+//    edge and the pairing starts again. On even cubes, when the corners are an odd permutation (PLL parity),
+//    the edges UF and UB are paired in each other's slot;
+// 4. the reduced cube (its corners, one wing of each edge and one sticker of each center) is a 3x3x3 cube,
+//    solved by a 3x3x3 solver with the face moves.
+
+/// The number of setup moves before the commutators which pair the edges
+const EDGE_SETUPS: usize = 2;
+
+/// The number of parity algorithms tried before giving up on the pairing of the edges
+const MAX_PARITIES: usize = 2;
+
+/// The parity algorithm which flips the edge UF, {0} being the inner layer of the swapped wings
+const OLL_PARITY: &str = "{0}R2 B2 U2 {0}L U2 {0}R' U2 {0}R U2 F2 {0}R F2 {0}L' B2 {0}R2";
+
+/// The reasons why a cube can not be reduced or solved
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReduceError {
+    /// The reduction is written for the 4x4x4 and 5x5x5 cubes
+    Size(usize),
+    /// The fixed centers of an odd cube do not have the colors of the scheme
+    Centers,
+    /// No algorithm was found for a piece: the cube is not legal
+    Stuck,
+    /// The reduced cube is not a legal 3x3x3 cube
+    Convert(ConvertError),
+    /// The 3x3x3 solver found no solution
+    Unsolved,
+}
+
+impl fmt::Display for ReduceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReduceError::Size(size) => write!(f, "The reduction needs a 4x4x4 or a 5x5x5 cube, not a {0}x{0}x{0}", size),
+            ReduceError::Centers => write!(f, "The fixed centers do not match the color scheme"),
+            ReduceError::Stuck => write!(f, "The cube can not be reduced, some pieces are not legal"),
+            ReduceError::Convert(error) => write!(f, "The reduced cube is not legal: {}", error),
+            ReduceError::Unsolved => write!(f, "The 3x3x3 solver found no solution"),
+        }
+    }
+}
+
+impl std::error::Error for ReduceError {}
+
+/// A reduced cube
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reduction {
+    /// The moves which reduce the cube
+    pub moves: Vec<BigMove>,
+    /// The reduced cube, as a 3x3x3 cube of the solver
+    pub cube: FaceCube,
+}
+
+/// A sequence of moves and its permutation of the stickers
+#[derive(Clone, Debug)]
+struct Alg {
+    moves: Vec<BigMove>,
+    perm: Perm,
+}
+
+impl Alg {
+    /// This function return the empty sequence on 'count' stickers
+    fn identity(count: usize) -> Self {
+        Alg { moves: Vec::new(), perm: (0..count).collect() }
+    }
+
+    /// This function return this sequence followed by another one
+    fn then(&self, other: &Alg) -> Self {
+        Alg {
+            moves: self.moves.iter().chain(&other.moves).copied().collect(),
+            perm: compose(&self.perm, &other.perm),
+        }
+    }
+
+    /// This function return the sequence which undoes this one
+    fn inverse(&self) -> Self {
+        Alg { moves: self.moves.iter().rev().map(|mv| mv.inverse()).collect(), perm: invert(&self.perm) }
+    }
+
+    /// This function return the commutator A B A' B'
+    fn commutator(a: &Alg, b: &Alg) -> Self {
+        a.then(b).then(&a.inverse()).then(&b.inverse())
+    }
+}
+
+/// A setup sequence with its inverse, see Reducer::search
+struct Setup {
+    setup: Alg,
+    undo: Alg,
+}
+
+/// A way to solve pieces: the algorithms of a library, tried behind each setup
+struct Method {
+    setups: Vec<Setup>,
+    library: Vec<Alg>,
+}
+
+/// The state of a cube during its reduction
+struct Reducer {
+    size: usize,
+    /// The colors of the stickers
+    state: Vec<Color>,
+    moves: Vec<BigMove>,
+    /// The color of the center of each face once solved
+    targets: [Color; 6],
+    /// The stickers already solved, with their colors, which must not move anymore
+    keep: Vec<(usize, Color)>,
+    /// The permutations of the moves
+    table: MoveTable,
+    /// The stickers of the pieces (1 for a center, 2 for an edge, 3 for a corner)
+    pieces: Vec<Vec<usize>>,
+}
+
+impl Reducer {
+    fn new(cube: &Cube, targets: [Color; 6]) -> Self {
+        let size = cube.get_size();
+        let state = FlatCube::from(cube).stickers().to_vec();
+        let table = MoveTable::new(size);
+
+        let pieces = table.pieces();
+
+        Reducer { size, state, moves: Vec::new(), targets, keep: Vec::new(), table, pieces }
+    }
+
+    /// This function return the face, the row and the column of a sticker
+    fn position(&self, sticker: usize) -> (usize, usize, usize) {
+        (sticker / (self.size * self.size), (sticker / self.size) % self.size, sticker % self.size)
+    }
+
+    /// This function return the index of a sticker
+    fn sticker(&self, face: usize, row: usize, col: usize) -> usize {
+        (face * self.size + row) * self.size + col
+    }
+
+    /// This function return the sequence of a single move
+    fn alg(&self, mv: BigMove) -> Alg {
+        Alg { moves: vec![mv], perm: self.table.big_move(mv).unwrap() }
+    }
+
+    /// This function return the sequence of an algorithm written in the big cube notation
+    fn parse(&self, algorithm: &str) -> Alg {
+        parse_algorithm(algorithm)
+            .unwrap()
+            .into_iter()
+            .fold(Alg::identity(self.state.len()), |alg, mv| alg.then(&self.alg(mv)))
+    }
+
+    /// This function return the moves of one layer, in the three directions
+    ///
+    /// # Args
+    /// * 'layers' - the layers to turn, numbered from 1 at the faces R, U and F
+    fn layer_moves(&self, layers: &[usize]) -> Vec<Alg> {
+        let mut moves = Vec::new();
+        for face in [Face::R, Face::U, Face::F] {
+            for layer in layers {
+                for turns in 1..=3 {
+                    moves.push(self.alg(BigMove { face, layers: Layers::Range(*layer, *layer), turns }));
+                }
+            }
+        }
+        moves
+    }
+
+    /// This function return the setups of at most 'depth' moves (the sequences which give the same
+    /// permutation are kept once)
+    fn setups(&self, moves: &[Alg], depth: usize) -> Vec<Setup> {
+        let mut known = HashSet::new();
+        let mut last = vec![Alg::identity(self.state.len())];
+        known.insert(last[0].perm.clone());
+        let mut setups = vec![Setup { setup: last[0].clone(), undo: last[0].clone() }];
+        for _ in 0..depth {
+            let mut next = Vec::new();
+            for first in &last {
+                for mv in moves {
+                    let setup = first.then(mv);
+                    if known.insert(setup.perm.clone()) {
+                        next.push(setup);
+                    }
+                }
+            }
+            setups.extend(next.iter().map(|setup| Setup { setup: setup.clone(), undo: setup.inverse() }));
+            last = next;
+        }
+        setups
+    }
+
+    /// This function play a sequence on the cube
+    fn play(&mut self, alg: &Alg) {
+        self.state = alg.perm.iter().map(|i| self.state[*i]).collect();
+        self.moves.extend(&alg.moves);
+    }
+
+    /// This function search a sequence which solves some stickers without moving the stickers already solved.
+    /// The methods are tried in order, the sequences tried are (setup, algorithm, undo).
+    fn search(&self, stickers: &[(usize, Color)], methods: &[Method]) -> Option<Alg> {
+        for Method { setups, library } in methods {
+            for Setup { setup, undo } in setups {
+                for alg in library {
+                    let solves = |(sticker, color): &(usize, Color)| {
+                        self.state[setup.perm[alg.perm[undo.perm[*sticker]]]] == *color
+                    };
+                    if stickers.iter().all(solves) && self.keep.iter().all(solves) {
+                        return Some(setup.then(alg).then(undo));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// This function solve a list of pieces one after the other, and add them to the stickers to keep
+    ///
+    /// # Return
+    ///
+    /// The index of the first piece which can not be solved, if any
+    fn solve_pieces(&mut self, pieces: &[Vec<(usize, Color)>], methods: &[Method]) -> Result<(), usize> {
+        for (index, piece) in pieces.iter().enumerate() {
+            if !piece.iter().all(|(sticker, color)| self.state[*sticker] == *color) {
+                let alg = self.search(piece, methods).ok_or(index)?;
+                self.play(&alg);
+            }
+            self.keep.extend(piece);
+        }
+        Ok(())
+    }
+
+    /// This function turn the whole cube so that its fixed centers (on odd cubes) have the colors of the scheme
+    fn orient(&mut self) -> Result<(), ReduceError> {
+        if self.size.is_multiple_of(2) {
+            return Ok(());
+        }
+        let middle = self.size / 2;
+        for first in ["", "x", "x2", "x'", "z", "z'"] {
+            for second in ["", "y", "y2", "y'"] {
+                let alg = self.parse(&format!("{} {}", first, second));
+                let oriented = (0..6).all(|face| {
+                    self.state[alg.perm[self.sticker(face, middle, middle)]] == self.targets[face]
+                });
+                if oriented {
+                    self.play(&alg);
+                    return Ok(());
+                }
+            }
+        }
+        Err(ReduceError::Centers)
+    }
+
+    /// This function return the methods which solve the centers: the short sequences, then the commutators of
+    /// an inner layer and of a face, then the commutators of an inner layer and of a face behind an inner layer
+    fn center_methods(&self) -> Vec<Method> {
+        let size = self.size;
+        let identity = Alg::identity(self.state.len());
+        let all_layers: Vec<usize> = (1..=size).collect();
+        let inner_layers: Vec<usize> = (2..size).collect();
+        let basic = self.layer_moves(&all_layers);
+        let inner = self.layer_moves(&inner_layers);
+        let faces = self.layer_moves(&[1, size]);
+
+        let mut face_commutators = Vec::new();
+        let mut layer_commutators = Vec::new();
+        let mut known = HashSet::new();
+        for a in &inner {
+            for b in faces.iter().filter(|b| !same_axis(b.moves[0].face, a.moves[0].face)) {
+                let commutator = Alg::commutator(a, b);
+                if known.insert(commutator.perm.clone()) {
+                    face_commutators.push(commutator);
+                }
+                for w in inner.iter().filter(|w| !same_axis(w.moves[0].face, b.moves[0].face)) {
+                    let commutator = Alg::commutator(a, &w.then(b).then(&w.inverse()));
+                    if known.insert(commutator.perm.clone()) {
+                        layer_commutators.push(commutator);
+                    }
+                }
+            }
+        }
+        let short = self.setups(&basic, 2);
+        vec![
+            Method {
+                setups: vec![Setup { setup: identity.clone(), undo: identity }],
+                library: short.iter().map(|setup| setup.setup.clone()).collect(),
+            },
+            Method { setups: self.setups(&basic, 1), library: layer_commutators },
+            Method { setups: short, library: face_commutators },
+        ]
+    }
+
+    /// This function solve the centers, one sticker after the other
+    fn solve_centers(&mut self, methods: &[Method]) -> Result<(), ReduceError> {
+        let size = self.size;
+        // The fixed centers of the odd cubes are already solved
+        self.keep.clear();
+        if !size.is_multiple_of(2) {
+            self.keep = (0..6).map(|face| (self.sticker(face, size / 2, size / 2), self.targets[face])).collect();
+        }
+        for face in [UP, DOWN, FRONT, BACK, LEFT, RIGHT] {
+            for row in 1..size - 1 {
+                for col in 1..size - 1 {
+                    let piece = vec![(self.sticker(face, row, col), self.targets[face])];
+                    self.solve_pieces(&[piece], methods).map_err(|_| ReduceError::Stuck)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// This function return the edges (pieces of two stickers), with the index of their orbit: the distance to the
+    /// nearest corner, the middle edges of the odd cubes being the orbit size / 2
+    fn edges(&self) -> Vec<(usize, Vec<usize>)> {
+        let last = self.size - 1;
+        self.pieces
+            .iter()
+            .filter(|stickers| stickers.len() == 2)
+            .map(|stickers| {
+                let (_, row, col) = self.position(stickers[0]);
+                let along = if row == 0 || row == last { col } else { row };
+                (along.min(last - along), stickers.clone())
+            })
+            .collect()
+    }
+
+    /// This function return the colors of the solved edges: the colors of the middle edge of the slot on odd
+    /// cubes, the colors of the centers on even cubes (with the slots UF and UB swapped for the PLL parity)
+    fn edge_targets(&self) -> Result<Vec<(usize, Color)>, ReduceError> {
+        let edges = self.edges();
+        let middle = self.size / 2;
+        let face_of = |sticker: usize| self.position(sticker).0;
+        let mut targets: Vec<Color> = (0..self.state.len()).map(|sticker| self.targets[face_of(sticker)]).collect();
+
+        if !self.size.is_multiple_of(2) {
+            for (orbit, stickers) in edges.iter().filter(|(orbit, _)| *orbit == middle) {
+                for (_, wing) in edges.iter().filter(|(o, wing)| o != orbit && same_slot(wing, stickers, face_of)) {
+                    for (sticker, midge) in wing.iter().flat_map(|s| stickers.iter().map(move |m| (*s, *m))) {
+                        if face_of(sticker) == face_of(midge) {
+                            targets[sticker] = self.state[midge];
+                        }
+                    }
+                }
+            }
+        } else if self.corner_parity()? {
+            for (_, stickers) in edges.iter() {
+                for sticker in stickers {
+                    let faces: Vec<usize> = stickers.iter().map(|s| face_of(*s)).collect();
+                    if faces.contains(&UP) && face_of(*sticker) == FRONT {
+                        targets[*sticker] = self.targets[BACK];
+                    } else if faces.contains(&UP) && face_of(*sticker) == BACK {
+                        targets[*sticker] = self.targets[FRONT];
+                    }
+                }
+            }
+        }
+        Ok(edges
+            .iter()
+            .filter(|(orbit, _)| *orbit != middle || self.size.is_multiple_of(2))
+            .flat_map(|(_, stickers)| stickers.iter().map(|sticker| (*sticker, targets[*sticker])))
+            .collect())
+    }
+
+    /// This function return true if the corners of an even cube are an odd permutation
+    fn corner_parity(&self) -> Result<bool, ReduceError> {
+        let mut small = self.small_cube();
+        // The edges of the 3x3x3 cube are solved
+        for face in 0..6 {
+            for (row, col) in [(0, 1), (1, 0), (1, 2), (2, 1)] {
+                small.set_sticker(face, row, col, self.targets[face]);
+            }
+        }
+        match to_cubie_cube(&small, &self.scheme()) {
+            Ok(_) => Ok(false),
+            Err(ConvertError::Illegal(CubeError::Parity)) => Ok(true),
+            Err(error) => Err(ReduceError::Convert(error)),
+        }
+    }
+
+    /// This function return the color scheme of the centers
+    fn scheme(&self) -> ColorScheme {
+        let mut colors = [Color::Empty; 6];
+        for (face, index) in FACE_INDEX.iter().enumerate() {
+            colors[face] = self.targets[*index];
+        }
+        ColorScheme::new(colors).unwrap()
+    }
+
+    /// This function return the 3x3x3 cube made of the corners, of a wing of each edge and of a sticker of each
+    /// center (the middle ones on odd cubes)
+    fn small_cube(&self) -> Cube {
+        let index = [0, self.size / 2, self.size - 1];
+        let mut small = Cube::new_filled(3);
+        for face in 0..6 {
+            for row in 0..3 {
+                for col in 0..3 {
+                    small.set_sticker(face, row, col, self.state[self.sticker(face, index[row], index[col])]);
+                }
+            }
+        }
+        small
+    }
+
+    /// This function return the method which pairs the edges: the commutators of an inner layer and of an
+    /// insertion X Y X' of faces which only move wings, behind setups of any layers
+    fn edge_methods(&self) -> Vec<Method> {
+        let size = self.size;
+        // The middle layer of the odd cubes would move the middle edges
+        let inner_layers: Vec<usize> = (2..size).filter(|layer| size.is_multiple_of(2) || *layer != size / 2 + 1).collect();
+        let all_layers: Vec<usize> = (1..=size).collect();
+        let faces = self.layer_moves(&[1, size]);
+        let wings: Vec<usize> = self.edges().into_iter().flat_map(|(_, stickers)| stickers).collect();
+
+        let mut library = Vec::new();
+        let mut known = HashSet::new();
+        for a in self.layer_moves(&inner_layers) {
+            for x in &faces {
+                for y in faces.iter().filter(|y| !same_axis(y.moves[0].face, x.moves[0].face)) {
+                    let insertion = x.then(y).then(&x.inverse());
+                    for commutator in [Alg::commutator(&a, &insertion), Alg::commutator(&insertion, &a)] {
+                        let only_wings = (0..self.state.len()).all(|sticker| commutator.perm[sticker] == sticker || wings.contains(&sticker));
+                        if only_wings && known.insert(commutator.perm.clone()) {
+                            library.push(commutator);
+                        }
+                    }
+                }
+            }
+        }
+        vec![Method { setups: self.setups(&self.layer_moves(&all_layers), EDGE_SETUPS), library }]
+    }
+
+    /// This function pair the edges, the centers being solved
+    ///
+    /// # Return
+    ///
+    /// The layer of the wings which can not be paired (their orbit is an odd permutation), if any
+    fn pair_edges(&mut self, methods: &[Method]) -> Result<Option<usize>, ReduceError> {
+        let size = self.size;
+        let targets = self.edge_targets()?;
+        let pieces: Vec<Vec<(usize, Color)>> = targets.chunks(2).map(|piece| piece.to_vec()).collect();
+        // The algorithms only move wings
+        self.keep.clear();
+        match self.solve_pieces(&pieces, methods) {
+            Ok(()) => Ok(None),
+            Err(index) => {
+                let (_, row, col) = self.position(pieces[index][0].0);
+                let along = if row == 0 || row == size - 1 { col } else { row };
+                Ok(Some(along.min(size - 1 - along) + 1))
+            }
+        }
+    }
+}
+
+/// This function return true if two faces are on the same axis
+fn same_axis(first: Face, second: Face) -> bool {
+    (first as usize) % 3 == (second as usize) % 3
+}
+
+/// This function return true if two edges are in the same slot (on the same faces)
+fn same_slot(first: &[usize], second: &[usize], face_of: impl Fn(usize) -> usize) -> bool {
+    let faces = |stickers: &[usize]| {
+        let mut faces: Vec<usize> = stickers.iter().map(|sticker| face_of(*sticker)).collect();
+        faces.sort();
+        faces
+    };
+    faces(first) == faces(second)
+}
+
+/// This function merge the following moves of the same layers (R R -> R2, R R' -> nothing)
+fn simplify(moves: &[BigMove]) -> Vec<BigMove> {
+    let mut simplified: Vec<BigMove> = Vec::new();
+    for mv in moves {
+        match simplified.last_mut() {
+            Some(last) if last.face == mv.face && last.layers == mv.layers => {
+                last.turns = (last.turns + mv.turns) % 4;
+                if last.turns == 0 {
+                    simplified.pop();
+                }
+            }
+            _ => simplified.push(*mv),
+        }
+    }
+    simplified
+}
+
+/// This function reduce a 4x4x4 or a 5x5x5 cube to a 3x3x3 cube: the centers are solved and the edges paired
+///
+/// # Args
+/// * 'cube' - the cube to reduce
+/// * 'scheme' - the colors of the faces of the solved cube
+pub fn reduce(cube: &Cube, scheme: &ColorScheme) -> Result<Reduction, ReduceError> {
+    let size = cube.get_size();
+    if !(4..=5).contains(&size) {
+        return Err(ReduceError::Size(size));
+    }
+    let mut targets = [Color::Empty; 6];
+    for (face, index) in FACE_INDEX.iter().enumerate() {
+        targets[*index] = scheme.color(Face::from(face));
+    }
+
+    let mut reducer = Reducer::new(cube, targets);
+    let center_methods = reducer.center_methods();
+    let edge_methods = reducer.edge_methods();
+    reducer.orient()?;
+    reducer.solve_centers(&center_methods)?;
+    let mut parities = 0;
+    // The parity algorithm keeps the centers
+    while let Some(layer) = reducer.pair_edges(&edge_methods)? {
+        parities += 1;
+        if parities > MAX_PARITIES {
+            return Err(ReduceError::Stuck);
+        }
+        let parity = reducer.parse(&OLL_PARITY.replace("{0}", &layer.to_string()));
+        reducer.play(&parity);
+    }
+
+    let small = reducer.small_cube();
+    to_cubie_cube(&small, scheme).map_err(ReduceError::Convert)?;
+    Ok(Reduction {
+        moves: simplify(&reducer.moves),
+        cube: to_face_cube(&small, scheme).map_err(ReduceError::Convert)?,
+    })
+}
+
+/// This function solve a 4x4x4 or a 5x5x5 cube: the cube is reduced, then the reduced cube is solved by a
+/// 3x3x3 solver, whose face moves turn the outer layers of the big cube.
+/// The solver crate has no complete 3x3x3 solver yet, so it is given by the caller.
+///
+/// # Args
+/// * 'cube' - the cube to solve
+/// * 'scheme' - the colors of the faces of the solved cube
+/// * 'solver' - the 3x3x3 solver, which return the moves solving a cube (None if it found no solution)
+pub fn solve<F>(cube: &Cube, scheme: &ColorScheme, solver: F) -> Result<Vec<BigMove>, ReduceError>
+where
+    F: FnOnce(&FaceCube) -> Option<Vec<Move>>,
+{
+    let reduction = reduce(cube, scheme)?;
+    let solution = solver(&reduction.cube).ok_or(ReduceError::Unsolved)?;
+    let moves: Vec<BigMove> = reduction.moves.into_iter().chain(solution.into_iter().map(BigMove::from_face_move)).collect();
+    Ok(simplify(&moves))
+}
+
@@ -0,0 +1,81 @@
+use crate::cube::Color;
+use rubiks_2000::cube::enums::Color as Face;
+use rubiks_2000::render::theme::{ Rgb, Theme };
+use std::fmt;
+
+/// The mapping between the faces of the solver (U, R, F, D, L, B) and the real colors of the stickers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ColorScheme {
+    /// The color of each face, indexed by the solver Color enum (U, R, F, D, L, B)
+    colors: [Color; 6],
+}
+
+/// The reasons why a color scheme is not valid
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SchemeError {
+    /// Two faces have the same color
+    Duplicate(Color),
+    /// A face has the Empty color
+    Empty,
+}
+
+impl fmt::Display for SchemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemeError::Duplicate(color) => write!(f, "The color {} is used by two faces", color),
+            SchemeError::Empty => write!(f, "A face has no color"),
+        }
+    }
+}
+
+impl std::error::Error for SchemeError {}
+
+impl ColorScheme {
+    /// The scheme of Cube::new_filled: white front, blue up, orange right
+    pub const DELTA: ColorScheme = ColorScheme {
+        colors: [Color::Blue, Color::Orange, Color::White, Color::Green, Color::Red, Color::Yellow],
+    };
+
+    /// The usual western scheme: white up, green front, red right
+    pub const WESTERN: ColorScheme = ColorScheme {
+        colors: [Color::White, Color::Red, Color::Green, Color::Yellow, Color::Orange, Color::Blue],
+    };
+
+    /// This function create a color scheme
+    ///
+    /// # Args
+    /// * 'colors' - the color of each face, in the order U, R, F, D, L, B
+    pub fn new(colors: [Color; 6]) -> Result<Self, SchemeError> {
+        for (i, color) in colors.iter().enumerate() {
+            if *color == Color::Empty {
+                return Err(SchemeError::Empty);
+            }
+            if colors[..i].contains(color) {
+                return Err(SchemeError::Duplicate(*color));
+            }
+        }
+        Ok(ColorScheme { colors })
+    }
+
+    /// This function return the color of a face
+    pub fn color(&self, face: Face) -> Color {
+        self.colors[face as usize]
+    }
+
+    /// This function return the face of a color, None for the Empty color
+    pub fn face(&self, color: Color) -> Option<Face> {
+        self.colors.iter().position(|c| *c == color).map(Face::from)
+    }
+
+    /// This function return the color drawn for a sticker: the color of its face in the theme.
+    /// None for the Empty color.
+    pub fn rgb(&self, theme: &Theme, color: Color) -> Option<Rgb> {
+        self.face(color).map(|face| theme.faces[face as usize])
+    }
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        ColorScheme::DELTA
+    }
+}
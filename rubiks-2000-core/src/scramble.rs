@@ -0,0 +1,235 @@
+use crate::notation::{ BigMove, Layers };
+use rand::Rng;
+use rubiks_2000::cube::cube::Cube as CubieCube;
+use rubiks_2000::cube::defs::{ EO_DEFAULT, EP_DEFAULT };
+use rubiks_2000::cube::enums::{ Color as Face, Corner, Move };
+use std::fmt;
+
+// The scramblers of the WCA:
+//
+// - the 2x2x2 cube is scrambled by a random state: a state is drawn among the 3,674,160 states, then the
+//   scramble is the inverse of its optimal solution. The optimal solutions are read in a table of the distance
+//   of each state to the solved cube (a breadth-first search with the moves U, R and F, which keep the corner
+//   DBL in place);
+// - the bigger cubes are scrambled by random moves of the faces and of the wide layers, without redundant
+//   moves: a move never follows a move of the same layers, and never follows two moves of the same axis.
+
+/// The corners moved by U, R and F, in the order of the permutation coordinate (DBL stays in place)
+const CORNERS: [Corner; 7] = [Corner::URF, Corner::UFL, Corner::ULB, Corner::UBR, Corner::DFR, Corner::DLF, Corner::DRB];
+
+/// The number of permutations of the 7 corners
+const N_PERMS: usize = 5040;
+
+/// The number of orientations of the 7 corners (the last one is given by the others)
+const N_TWISTS: usize = 729;
+
+/// The moves of the 2x2x2 solver: U1, U2, U3, R1... F3 (the first moves of the solver)
+const N_MOVES_2X2: usize = 9;
+
+/// The states nearer to the solved cube are drawn again, as by the scrambler of the WCA
+const MIN_DISTANCE_2X2: u8 = 4;
+
+/// The reasons why a 2x2x2 cube can not be solved
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScrambleError {
+    /// The corner DBL, which the solver does not move, is not in place
+    Reference,
+}
+
+impl fmt::Display for ScrambleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScrambleError::Reference => write!(f, "The corner DBL must be solved"),
+        }
+    }
+}
+
+impl std::error::Error for ScrambleError {}
+
+/// This function return the index of a permutation of 0..7 (its rank in the lexicographic order)
+fn perm_index(perm: &[usize; 7]) -> usize {
+    (0..7).fold(0, |index, i| index * (7 - i) + perm[i + 1..].iter().filter(|other| **other < perm[i]).count())
+}
+
+/// This function return the permutation of an index, inverse of perm_index
+fn perm_from_index(mut index: usize) -> [usize; 7] {
+    let mut digits = [0; 7];
+    for i in (0..7).rev() {
+        digits[i] = index % (7 - i);
+        index /= 7 - i;
+    }
+    let mut remaining: Vec<usize> = (0..7).collect();
+    digits.map(|digit| remaining.remove(digit))
+}
+
+/// The optimal solver of the 2x2x2 cube, which also gives its random state scrambles
+pub struct TwoByTwo {
+    /// The permutation coordinate after each move
+    perm_moves: Vec<[u16; N_MOVES_2X2]>,
+    /// The twist coordinate after each move
+    twist_moves: Vec<[u16; N_MOVES_2X2]>,
+    /// The number of moves of the optimal solution of each state (perm * N_TWISTS + twist)
+    distances: Vec<u8>,
+}
+
+impl TwoByTwo {
+    /// This function build the tables of the solver (a breadth-first search over all the states)
+    pub fn new() -> Self {
+        let moves: Vec<CubieCube> = (0..N_MOVES_2X2).map(|mv| Move::from(mv).get_cube()).collect();
+        let perm_moves = (0..N_PERMS)
+            .map(|perm| {
+                let mut table = [0; N_MOVES_2X2];
+                for (next, mv) in table.iter_mut().zip(&moves) {
+                    let mut cube = TwoByTwo::cube(perm, 0);
+                    cube.corner_multiply(*mv);
+                    *next = TwoByTwo::coordinates(&cube).0 as u16;
+                }
+                table
+            })
+            .collect();
+        let twist_moves = (0..N_TWISTS)
+            .map(|twist| {
+                let mut table = [0; N_MOVES_2X2];
+                for (next, mv) in table.iter_mut().zip(&moves) {
+                    let mut cube = TwoByTwo::cube(0, twist);
+                    cube.corner_multiply(*mv);
+                    *next = TwoByTwo::coordinates(&cube).1 as u16;
+                }
+                table
+            })
+            .collect();
+        let mut solver = TwoByTwo { perm_moves, twist_moves, distances: vec![u8::MAX; N_PERMS * N_TWISTS] };
+
+        let solved = TwoByTwo::coordinates(&TwoByTwo::cube(0, 0));
+        solver.distances[solved.0 * N_TWISTS + solved.1] = 0;
+        let mut queue = vec![solved];
+        let mut next = 0;
+        while next < queue.len() {
+            let (perm, twist) = queue[next];
+            let distance = solver.distances[perm * N_TWISTS + twist];
+            for mv in 0..N_MOVES_2X2 {
+                let moved = solver.apply((perm, twist), mv);
+                if solver.distances[moved.0 * N_TWISTS + moved.1] == u8::MAX {
+                    solver.distances[moved.0 * N_TWISTS + moved.1] = distance + 1;
+                    queue.push(moved);
+                }
+            }
+            next += 1;
+        }
+        solver
+    }
+
+    /// This function return the cubie cube of some coordinates (the edges are solved)
+    fn cube(perm: usize, twist: usize) -> CubieCube {
+        let mut cp = [Corner::DBL; 8];
+        for (position, corner) in CORNERS.iter().zip(perm_from_index(perm)) {
+            cp[*position as usize] = CORNERS[corner];
+        }
+        let mut co = [0; 8];
+        let mut rest = twist;
+        for position in CORNERS[..6].iter().rev() {
+            co[*position as usize] = (rest % 3) as u8;
+            rest /= 3;
+        }
+        co[Corner::DRB as usize] = (3 - co.iter().sum::<u8>() % 3) % 3;
+        CubieCube::new(cp, co, EP_DEFAULT, EO_DEFAULT)
+    }
+
+    /// This function return the coordinates of the corners of a cubie cube, whose corner DBL is solved
+    fn coordinates(cube: &CubieCube) -> (usize, usize) {
+        let (cp, co) = (cube.get_cp(), cube.get_co());
+        let perm = CORNERS.map(|position| CORNERS.iter().position(|corner| *corner == cp[position as usize]).unwrap());
+        let twist = CORNERS[..6].iter().fold(0, |twist, position| 3 * twist + co[*position as usize] as usize);
+        (perm_index(&perm), twist)
+    }
+
+    /// This function return the coordinates after a move
+    fn apply(&self, (perm, twist): (usize, usize), mv: usize) -> (usize, usize) {
+        (self.perm_moves[perm][mv] as usize, self.twist_moves[twist][mv] as usize)
+    }
+
+    /// This function return an optimal solution (in half turns) of the corners of a cubie cube
+    pub fn solve(&self, cube: &CubieCube) -> Result<Vec<Move>, ScrambleError> {
+        let dbl = Corner::DBL as usize;
+        if cube.get_cp()[dbl] != Corner::DBL || cube.get_co()[dbl] != 0 {
+            return Err(ScrambleError::Reference);
+        }
+        Ok(self.solve_coordinates(TwoByTwo::coordinates(cube)))
+    }
+
+    /// This function return an optimal solution of some coordinates: each move goes one step nearer to the solved cube
+    fn solve_coordinates(&self, mut state: (usize, usize)) -> Vec<Move> {
+        let mut solution = Vec::new();
+        let mut distance = self.distances[state.0 * N_TWISTS + state.1];
+        while distance > 0 {
+            let mv = (0..N_MOVES_2X2)
+                .find(|mv| {
+                    let moved = self.apply(state, *mv);
+                    self.distances[moved.0 * N_TWISTS + moved.1] == distance - 1
+                })
+                .unwrap();
+            state = self.apply(state, mv);
+            distance -= 1;
+            solution.push(Move::from(mv));
+        }
+        solution
+    }
+
+    /// This function return a random state scramble: the inverse of the optimal solution of a random state
+    pub fn scramble(&self) -> Vec<BigMove> {
+        let mut rng = rand::thread_rng();
+        loop {
+            let state = (rng.gen_range(0..N_PERMS), rng.gen_range(0..N_TWISTS));
+            if self.distances[state.0 * N_TWISTS + state.1] >= MIN_DISTANCE_2X2 {
+                let solution = self.solve_coordinates(state);
+                return solution.iter().rev().map(|mv| BigMove::from_face_move(*mv).inverse()).collect();
+            }
+        }
+    }
+}
+
+impl Default for TwoByTwo {
+    fn default() -> Self {
+        TwoByTwo::new()
+    }
+}
+
+/// This function return the number of moves of the random move scrambles of the WCA (40 for the 4x4x4 cube,
+/// 60 for the 5x5x5, 80 for the 6x6x6, 100 for the 7x7x7)
+pub fn scramble_length(size: usize) -> usize {
+    20 * size.saturating_sub(2).max(1)
+}
+
+/// This function return true if two moves turn the layers of the same axis
+fn same_axis(first: &BigMove, second: &BigMove) -> bool {
+    (first.face as usize) % 3 == (second.face as usize) % 3
+}
+
+/// This function return a random move scramble of a cube of size 'size': random turns of the faces and of the
+/// wide layers (up to half the cube), without redundant moves (see the header of this file)
+///
+/// # Args
+/// * 'size' - the size of the cube, 2 at least
+/// * 'length' - the number of moves, see scramble_length
+pub fn random_move_scramble(size: usize, length: usize) -> Vec<BigMove> {
+    let mut rng = rand::thread_rng();
+    let mut moves: Vec<BigMove> = Vec::with_capacity(length);
+    while moves.len() < length {
+        let width = rng.gen_range(1..=(size / 2).max(1));
+        let face = Face::from(rng.gen_range(0..6));
+        // The half of an even cube turned from a face is the other half turned from the opposite face
+        if size.is_multiple_of(2) && width == size / 2 && width > 1 && !matches!(face, Face::R | Face::U | Face::F) {
+            continue;
+        }
+        let mv = BigMove { face, layers: Layers::Range(1, width), turns: rng.gen_range(1..=3) };
+        let redundant = match &moves[..] {
+            [.., last] if same_axis(last, &mv) && last.face == mv.face && last.layers == mv.layers => true,
+            [.., before, last] => same_axis(before, &mv) && same_axis(last, &mv),
+            _ => false,
+        };
+        if !redundant {
+            moves.push(mv);
+        }
+    }
+    moves
+}
@@ -0,0 +1,370 @@
+#[cfg(test)]
+mod tests {
+    use crate::convert::{ to_cubie_cube, to_face_cube, to_nxn, ConvertError };
+    use crate::cube::{ Color, Cube, BACK, FRONT, LEFT, RIGHT, UP };
+    use crate::flat::{ FlatCube, MoveTable, SLICE_MOVES };
+    use crate::moves::{ algorithm_to_slices, face_move_to_slices, slice_to_face_move, SliceMove };
+    use crate::notation::{ algorithm_to_string, parse_algorithm, BigMove, Layers, NotationError };
+    use crate::reduction::{ reduce, solve, ReduceError };
+    use crate::scheme::{ ColorScheme, SchemeError };
+    use crate::scramble::{ random_move_scramble, scramble_length, TwoByTwo };
+    use crate::validate::{ validate, ValidationError };
+    use rubiks_2000::cube::defs::DEFAULT;
+    use rubiks_2000::cube::enums::Color as Face;
+    use rubiks_2000::cube::enums::Move;
+    use rubiks_2000::cube::face_cube::FaceCube;
+    use rand::{ thread_rng, Rng };
+    use std::collections::HashMap;
+
+    /// A solution of at most 9 moves of a 3x3x3 cube, to test the reduction without a 3x3x3 solver: the cubes at 4
+    /// moves of the solved cube meet the cubes at 5 moves of `cube`
+    fn short_solution(cube: &FaceCube) -> Option<Vec<Move>> {
+        fn search(cube: FaceCube, depth: usize, path: &mut Vec<Move>, near: &HashMap<FaceCube, Vec<Move>>) -> Option<Vec<Move>> {
+            if let Some(end) = near.get(&cube) {
+                return Some(path.iter().copied().chain(end.iter().rev().map(|mv| mv.move_inv())).collect());
+            }
+            if depth == 0 {
+                return None;
+            }
+            for index in 0..18 {
+                let mv = Move::from(index);
+                if path.last().is_some_and(|&last| last.is_same_slice(mv)) {
+                    continue;
+                }
+                let mut next = cube;
+                next.apply_move(mv);
+                path.push(mv);
+                let solution = search(next, depth - 1, path, near);
+                path.pop();
+                if solution.is_some() {
+                    return solution;
+                }
+            }
+            None
+        }
+
+        let mut near = HashMap::from([(FaceCube::new(), Vec::new())]);
+        let mut layer = vec![FaceCube::new()];
+        for _ in 0..4 {
+            let mut next_layer = Vec::new();
+            for cube in layer {
+                for index in 0..18 {
+                    let mut next = cube;
+                    next.apply_move(Move::from(index));
+                    if !near.contains_key(&next) {
+                        let mut path = near[&cube].clone();
+                        path.push(Move::from(index));
+                        near.insert(next, path);
+                        next_layer.push(next);
+                    }
+                }
+            }
+            layer = next_layer;
+        }
+        search(*cube, 5, &mut Vec::new(), &near)
+    }
+
+    #[test]
+    /// The solved cube of the solver is the cube of new_filled
+    fn test_solved() {
+        assert_eq!(to_nxn(&FaceCube::new(), &ColorScheme::DELTA), Cube::new_filled(3));
+        assert_eq!(to_face_cube(&Cube::new_filled(3), &ColorScheme::DELTA), Ok(FaceCube::new()));
+    }
+
+    #[test]
+    /// The slice moves of a face move do the same thing as the face move of the solver
+    fn test_face_moves() {
+        for index in 0..18 {
+            let mv = Move::from(index);
+            let mut face_cube = FaceCube::new();
+            face_cube.apply_move(mv);
+            let mut nxn = Cube::new_filled(3);
+            for (slice, n) in face_move_to_slices(mv, 3) {
+                nxn.apply_slice_move(slice, n);
+            }
+            assert_eq!(to_nxn(&face_cube, &ColorScheme::DELTA), nxn, "Failed move - {}", mv);
+        }
+
+        // A random sequence, with the western colors
+        let mut rng = thread_rng();
+        let moves: Vec<Move> = (0..40).map(|_| Move::from(rng.gen_range(0..18))).collect();
+        let mut cube = DEFAULT;
+        let mut nxn = to_nxn(&FaceCube::new(), &ColorScheme::WESTERN);
+        for mv in &moves {
+            cube.multiply(mv.get_cube());
+        }
+        for (slice, n) in algorithm_to_slices(&moves, 3) {
+            nxn.apply_slice_move(slice, n);
+        }
+        assert_eq!(to_cubie_cube(&nxn, &ColorScheme::WESTERN), Ok(cube));
+        assert_eq!(to_face_cube(&nxn, &ColorScheme::WESTERN), Ok(cube.to_facelet_cube()));
+    }
+
+    #[test]
+    fn test_slice_to_face_move() {
+        for size in [2, 3, 5] {
+            for index in 0..18 {
+                let mv = Move::from(index);
+                if let [(slice, n)] = face_move_to_slices(mv, size)[..] {
+                    assert_eq!(slice_to_face_move(slice, n, size), Some(mv));
+                }
+            }
+        }
+        assert_eq!(slice_to_face_move(SliceMove::V, 2, 5), None);
+    }
+
+    #[test]
+    fn test_convert_errors() {
+        assert_eq!(to_face_cube(&Cube::new_filled(4), &ColorScheme::DELTA), Err(ConvertError::Size(4)));
+        assert_eq!(
+            to_face_cube(&Cube::new_filled(3), &ColorScheme::WESTERN).map(|cube| cube == FaceCube::new()),
+            Ok(false)
+        );
+        assert_eq!(to_cubie_cube(&Cube::new_filled(3), &ColorScheme::WESTERN), Err(ConvertError::Centers));
+
+        let mut nxn = Cube::new_filled(3);
+        nxn.set_sticker(0, 0, 0, Color::Empty);
+        assert_eq!(to_face_cube(&nxn, &ColorScheme::DELTA), Err(ConvertError::UnknownColor(Color::Empty)));
+
+        assert_eq!(
+            ColorScheme::new([Color::White, Color::Red, Color::Green, Color::Yellow, Color::Red, Color::Blue]),
+            Err(SchemeError::Duplicate(Color::Red))
+        );
+        assert_eq!(ColorScheme::new([Color::White, Color::Red, Color::Green, Color::Yellow, Color::Orange, Color::Blue]), Ok(ColorScheme::WESTERN));
+    }
+
+    #[test]
+    fn test_big_notation() {
+        // The printer writes back what the parser reads (the lower case wide moves are written with a w)
+        let algorithm = "R U' F2 2R 3Lw' Rw 2-4Rw2 M E' S2 x y' z2";
+        assert_eq!(parse_algorithm(algorithm).map(|moves| algorithm_to_string(&moves)), Ok(String::from(algorithm)));
+        assert_eq!(parse_algorithm("r 3u' 2-3f2").map(|moves| algorithm_to_string(&moves)), Ok(String::from("Rw 3Uw' 2-3Fw2")));
+        for wrong in ["Q", "Rww", "0R", "3-2Rw", "2-3R", "3x", "w"] {
+            assert_eq!(wrong.parse::<BigMove>(), Err(NotationError::Parse(String::from(wrong))));
+        }
+
+        // The face moves are the ones of the solver
+        for index in 0..18 {
+            let mv = Move::from(index);
+            let big_move = BigMove::from_face_move(mv);
+            assert_eq!(big_move.to_string(), mv.to_notation());
+            assert_eq!(big_move.to_slices(4), Ok(face_move_to_slices(mv, 4)));
+        }
+
+        // A wide move is the face and the inner slices, x is Rw L' on the 3x3x3 cube, M is x' R L'
+        let play = |algorithm: &str, size: usize| {
+            let mut cube = Cube::new_filled(size);
+            for mv in parse_algorithm(algorithm).unwrap() {
+                cube.apply_big_move(mv).unwrap();
+            }
+            cube
+        };
+        assert_eq!(play("3Rw", 5), play("R 2R 3R", 5));
+        assert_eq!(play("2-3Lw'", 5), play("2L' 3L'", 5));
+        assert_eq!(play("x", 3), play("Rw L'", 3));
+        assert_eq!(play("M", 3), play("x' R L'", 3));
+        assert_eq!(play("E2", 4), play("2D2 3D2", 4));
+        assert_eq!(Cube::new_filled(3).apply_big_move("4Rw".parse().unwrap()), Err(NotationError::Layers(String::from("4Rw"), 3)));
+        // The fields of BigMove are public: the ranges which can not be parsed are checked too
+        for layers in [Layers::Range(0, 1), Layers::Range(3, 2), Layers::Range(0, 0)] {
+            let mv = BigMove { face: Face::R, layers, turns: 1 };
+            assert_eq!(mv.to_slices(4), Err(NotationError::Layers(mv.to_string(), 4)));
+        }
+
+        // Each slice move has a name
+        for size in [2, 3, 4, 5] {
+            for mov in [SliceMove::V, SliceMove::Vp, SliceMove::H, SliceMove::Hp, SliceMove::L, SliceMove::Lp] {
+                for n in 0..size {
+                    assert_eq!(BigMove::from_slice(mov, n, size).to_slices(size), Ok(vec![(mov, n)]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_reduction() {
+        // The reduced cube has its centers solved and its edges paired: it is the 3x3x3 cube of the reduction
+        let reduced = |size: usize, scramble: &str| {
+            let mut cube = Cube::new_filled(size);
+            for mv in parse_algorithm(scramble).unwrap() {
+                cube.apply_big_move(mv).unwrap();
+            }
+            let reduction = reduce(&cube, &ColorScheme::DELTA).unwrap();
+            for mv in &reduction.moves {
+                cube.apply_big_move(*mv).unwrap();
+            }
+            let small = to_nxn(&reduction.cube, &ColorScheme::DELTA);
+            let small_index = |i: usize| if i == 0 { 0 } else if i == size - 1 { 2 } else { 1 };
+            for face in 0..6 {
+                for row in 0..size {
+                    for col in 0..size {
+                        let color = small.get_sticker(face, small_index(row), small_index(col));
+                        assert_eq!(cube.get_sticker(face, row, col), color, "Failed scramble - {}", scramble);
+                    }
+                }
+            }
+            reduction
+        };
+        reduced(5, "2R U 3F' Dw2 3Lw B' 2-4Uw");
+
+        // The OLL parity (one flipped pair of edges) and the PLL parity (two swapped pairs of edges) of the 4x4x4
+        // cube: the reduction fixes them and the cube is solved
+        for scramble in ["2R2 B2 U2 2L U2 2R' U2 2R U2 F2 2R F2 2L' B2 2R2", "2R2 U2 2R2 Uw2 2R2 2U2"] {
+            let reduction = reduced(4, scramble);
+            assert!(!reduction.moves.is_empty(), "Failed scramble - {}", scramble);
+            assert_eq!(reduction.cube, FaceCube::new(), "Failed scramble - {}", scramble);
+        }
+
+        // A scramble by wide moves needs the centers and the pairing of the edges, then the 3x3x3 solver
+        let scramble = parse_algorithm("Dw2 Lw2 Bw2 Lw2").unwrap();
+        let mut cube = Cube::new_filled(4);
+        for mv in &scramble {
+            cube.apply_big_move(*mv).unwrap();
+        }
+        let reduction = reduce(&cube, &ColorScheme::DELTA).unwrap();
+        assert_ne!(reduction.cube, FaceCube::new());
+        let solution = solve(&cube, &ColorScheme::DELTA, short_solution).unwrap();
+        assert!(solution.len() > reduction.moves.len());
+        assert_eq!(solution[..reduction.moves.len()], reduction.moves[..]);
+        for mv in &solution {
+            cube.apply_big_move(*mv).unwrap();
+        }
+        assert!(cube.is_solved());
+
+        // The face moves of the 3x3x3 solver finish the big cube (the wings of a 5x5x5 cube go with the middle
+        // edges, so a cube scrambled by face moves is already reduced)
+        let scramble = parse_algorithm("R U2 F' D L2 B").unwrap();
+        let mut cube = Cube::new_filled(5);
+        for mv in &scramble {
+            cube.apply_big_move(*mv).unwrap();
+        }
+        let solver = |_: &FaceCube| Some(vec![Move::B3, Move::L2, Move::D3, Move::F1, Move::U2, Move::R3]);
+        let solution = solve(&cube, &ColorScheme::DELTA, solver).unwrap();
+        assert_eq!(algorithm_to_string(&solution), "B' L2 D' F U2 R'");
+        for mv in &solution {
+            cube.apply_big_move(*mv).unwrap();
+        }
+        assert!(cube.is_solved());
+
+        assert_eq!(solve(&cube, &ColorScheme::DELTA, |_| None), Err(ReduceError::Unsolved));
+        assert_eq!(reduce(&Cube::new_filled(3), &ColorScheme::DELTA), Err(ReduceError::Size(3)));
+    }
+
+    #[test]
+    fn test_flat_cube() {
+        // The moves of the tables are the moves of the Cube
+        let mut rng = thread_rng();
+        let table = MoveTable::new(7);
+        let mut cube = Cube::new_filled(7);
+        let mut flat = FlatCube::from(&cube);
+        for _ in 0..50 {
+            let (mov, n) = (SLICE_MOVES[rng.gen_range(0..6)], rng.gen_range(0..7));
+            cube.apply_slice_move(mov, n);
+            flat.apply_slice_move(&table, mov, n);
+        }
+        for mv in parse_algorithm("3Rw' 2-4Uw M x 2F2").unwrap() {
+            cube.apply_big_move(mv).unwrap();
+            flat.apply_big_move(&table, mv).unwrap();
+        }
+        assert_eq!(Cube::from(&flat), cube);
+        assert_eq!(FlatCube::new_solved(7, &ColorScheme::DELTA), FlatCube::from(&Cube::new_filled(7)));
+        assert_eq!(Cube::new_empty(4).to_string(), "E".repeat(96));
+    }
+
+    #[test]
+    fn test_validation() {
+        for size in 1..=6 {
+            let mut cube = Cube::new_filled(size);
+            cube.shuffle(100);
+            assert_eq!(validate(&cube, &ColorScheme::DELTA), Ok(()), "Failed size - {}", size);
+        }
+
+        // The stickers of a piece of the 3x3x3 cube, from MoveTable::pieces
+        let pieces = MoveTable::new(3).pieces();
+        let swap = |cube: &mut Cube, first: usize, second: usize| {
+            let (a, b) = (cube.get_sticker(first / 9, (first / 3) % 3, first % 3), cube.get_sticker(second / 9, (second / 3) % 3, second % 3));
+            cube.set_sticker(first / 9, (first / 3) % 3, first % 3, b);
+            cube.set_sticker(second / 9, (second / 3) % 3, second % 3, a);
+        };
+        let corner = pieces.iter().find(|stickers| stickers.len() == 3).unwrap();
+        let mut twisted = Cube::new_filled(3);
+        swap(&mut twisted, corner[0], corner[1]);
+        swap(&mut twisted, corner[1], corner[2]);
+        assert_eq!(validate(&twisted, &ColorScheme::DELTA), Err(ValidationError::CornerTwist));
+        let edge = pieces.iter().find(|stickers| stickers.len() == 2).unwrap();
+        let mut flipped = Cube::new_filled(3);
+        swap(&mut flipped, edge[0], edge[1]);
+        assert_eq!(validate(&flipped, &ColorScheme::DELTA), Err(ValidationError::EdgeFlip));
+
+        // The edges UF and UB swapped, the left and right centers swapped
+        let mut swapped = Cube::new_filled(3);
+        swapped.set_sticker(FRONT, 0, 1, Cube::new_filled(3).get_sticker(BACK, 0, 1));
+        swapped.set_sticker(BACK, 0, 1, Cube::new_filled(3).get_sticker(FRONT, 0, 1));
+        assert_eq!(validate(&swapped, &ColorScheme::DELTA), Err(ValidationError::Parity));
+        let mut mirrored = Cube::new_filled(3);
+        mirrored.set_sticker(LEFT, 1, 1, Cube::new_filled(3).get_sticker(RIGHT, 1, 1));
+        mirrored.set_sticker(RIGHT, 1, 1, Cube::new_filled(3).get_sticker(LEFT, 1, 1));
+        assert_eq!(validate(&mirrored, &ColorScheme::DELTA), Err(ValidationError::Centers));
+
+        // A wing can not be flipped, but two wings can be swapped (OLL parity) on a 4x4x4 cube
+        let mut wing = Cube::new_filled(4);
+        let (up, front) = (wing.get_sticker(UP, 3, 1), wing.get_sticker(FRONT, 0, 1));
+        wing.set_sticker(UP, 3, 1, front);
+        wing.set_sticker(FRONT, 0, 1, up);
+        assert_eq!(validate(&wing, &ColorScheme::DELTA), Err(ValidationError::Edges));
+        let mut parity = Cube::new_filled(4);
+        for mv in parse_algorithm("2R2 B2 U2 2L U2 2R' U2 2R U2 F2 2R F2 2L' B2 2R2").unwrap() {
+            parity.apply_big_move(mv).unwrap();
+        }
+        assert_eq!(validate(&parity, &ColorScheme::DELTA), Ok(()));
+
+        let mut empty = Cube::new_filled(4);
+        empty.set_sticker(FRONT, 1, 1, Color::Empty);
+        assert_eq!(validate(&empty, &ColorScheme::DELTA), Err(ValidationError::Colors(Color::Empty)));
+    }
+
+    #[test]
+    fn test_scrambles() {
+        // The random state scramble of the 2x2x2 cube is optimal: its inverse is the solution of the solver
+        let solver = TwoByTwo::new();
+        let scramble = solver.scramble();
+        assert!((4..=11).contains(&scramble.len()), "Failed scramble - {}", algorithm_to_string(&scramble));
+        let mut cube = DEFAULT;
+        let mut nxn = Cube::new_filled(2);
+        for mv in &scramble {
+            cube.multiply(Move::from(3 * (mv.face as usize) + (mv.turns as usize) - 1).get_cube());
+            nxn.apply_big_move(*mv).unwrap();
+        }
+        assert_eq!(validate(&nxn, &ColorScheme::DELTA), Ok(()));
+        let solution = solver.solve(&cube).unwrap();
+        assert_eq!(solution.len(), scramble.len());
+        for mv in solution {
+            cube.multiply(mv.get_cube());
+        }
+        assert_eq!(cube.get_cp(), DEFAULT.get_cp());
+        assert_eq!(cube.get_co(), DEFAULT.get_co());
+
+        // The random move scrambles have no redundant moves, and their wide moves stay on half of the cube
+        for size in [4, 5, 7] {
+            let scramble = random_move_scramble(size, scramble_length(size));
+            assert_eq!(scramble.len(), 20 * (size - 2));
+            for (i, mv) in scramble.iter().enumerate() {
+                let width = match mv.layers {
+                    Layers::Range(1, width) => width,
+                    _ => panic!("Failed move - {}", mv),
+                };
+                assert!(width <= size / 2);
+                if width == 2 && size == 4 {
+                    assert!([Face::R, Face::U, Face::F].contains(&mv.face));
+                }
+                let axis = |other: &BigMove| (other.face as usize) % 3 == (mv.face as usize) % 3;
+                if i > 0 {
+                    assert!(scramble[i - 1].face != mv.face || scramble[i - 1].layers != mv.layers);
+                }
+                if i > 1 {
+                    assert!(!axis(&scramble[i - 1]) || !axis(&scramble[i - 2]));
+                }
+            }
+        }
+    }
+}
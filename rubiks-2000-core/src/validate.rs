@@ -0,0 +1,284 @@
+use crate::cube::{ Color, Cube, BACK, DOWN, FRONT, UP };
+use crate::flat::{ invert, FlatCube, MoveTable };
+use crate::scheme::ColorScheme;
+use std::fmt;
+
+// The validation of a NxN cube, for any size:
+//
+// 1. each color of the scheme has size² stickers;
+// 2. the pieces are real pieces: the stickers of each orbit of corners, wings and middle edges are the pieces of
+//    the solved cube, and each orbit of centers has the same colors as on the solved cube. The fixed centers
+//    of the odd cubes are turned like the solved cube (not mirrored);
+// 3. the invariants of the moves: the corners are not twisted, the middle edges are not flipped, and the
+//    parities of the permutations of the orbits are the parities of a sequence of moves (the orbits of
+//    centers, which have several pieces of each color, can have any parity).
+//
+// The orbits are found by playing the moves on the pieces. The stickers of a piece are ordered so that the
+// moves keep this order (up to a rotation for the corners and the middle edges): the twist of a corner is the
+// rotation of its stickers, the sticker on the face UP or DOWN being the first one on the solved cube.
+
+/// The reasons why a NxN cube can not be reached from the solved cube
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// A color is not in the color scheme, or does not have size² stickers
+    Colors(Color),
+    /// Some stickers do not form real corners
+    Corners,
+    /// Some stickers do not form real edges (middle edges or wings)
+    Edges,
+    /// Some centers are missing, or the fixed centers are mirrored
+    Centers,
+    /// The total twist of the corners is wrong
+    CornerTwist,
+    /// The total flip of the middle edges is wrong
+    EdgeFlip,
+    /// The permutations of the pieces can not be reached together
+    Parity,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::Colors(color) => write!(f, "The color {} does not have the right number of stickers", color),
+            ValidationError::Corners => write!(f, "Some stickers do not form real corners"),
+            ValidationError::Edges => write!(f, "Some stickers do not form real edges"),
+            ValidationError::Centers => write!(f, "The centers do not match the color scheme"),
+            ValidationError::CornerTwist => write!(f, "Total corner twist is wrong"),
+            ValidationError::EdgeFlip => write!(f, "Total edge flip is wrong"),
+            ValidationError::Parity => write!(f, "Wrong parities of the pieces"),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// The kinds of pieces
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    Corner,
+    /// The middle edges of the odd cubes, which can be flipped
+    Midge,
+    /// The other edges, which can not be flipped
+    Wing,
+    Center,
+}
+
+/// The positions of the pieces which the moves exchange
+struct Orbit {
+    kind: Kind,
+    /// The stickers of each position, in the order kept by the moves
+    positions: Vec<Vec<usize>>,
+}
+
+/// This function return the orbits of the pieces of a size of cube
+fn orbits(table: &MoveTable) -> Vec<Orbit> {
+    let size = table.get_size();
+    let pieces = table.pieces();
+    let mut piece_of = vec![0; table.len()];
+    for (index, stickers) in pieces.iter().enumerate() {
+        for sticker in stickers {
+            piece_of[*sticker] = index;
+        }
+    }
+    // The sticker i goes to targets[i]
+    let targets: Vec<Vec<usize>> = table.slices().map(invert).collect();
+
+    let mut ordered: Vec<Option<Vec<usize>>> = vec![None; pieces.len()];
+    let mut orbits = Vec::new();
+    for start in 0..pieces.len() {
+        if ordered[start].is_some() {
+            continue;
+        }
+        ordered[start] = Some(pieces[start].clone());
+        let mut positions = vec![pieces[start].clone()];
+        let mut next = 0;
+        while next < positions.len() {
+            for target in &targets {
+                let moved: Vec<usize> = positions[next].iter().map(|sticker| target[*sticker]).collect();
+                let piece = piece_of[moved[0]];
+                if ordered[piece].is_none() {
+                    ordered[piece] = Some(moved.clone());
+                    positions.push(moved);
+                }
+            }
+            next += 1;
+        }
+        let kind = match (positions[0].len(), positions.len()) {
+            (3, _) => Kind::Corner,
+            (2, 12) => Kind::Midge,
+            (2, _) => Kind::Wing,
+            _ => Kind::Center,
+        };
+        // The reference orientations: the sticker on UP or DOWN first, then the one on FRONT or BACK
+        let face_of = |sticker: usize| sticker / (size * size);
+        for stickers in positions.iter_mut() {
+            let first = match kind {
+                Kind::Corner | Kind::Midge => stickers.iter().position(|s| [UP, DOWN].contains(&face_of(*s))),
+                _ => None,
+            };
+            let first = first.or(match kind {
+                Kind::Midge => stickers.iter().position(|s| [FRONT, BACK].contains(&face_of(*s))),
+                _ => None,
+            });
+            stickers.rotate_left(first.unwrap_or(0));
+        }
+        orbits.push(Orbit { kind, positions });
+    }
+    orbits
+}
+
+/// This function return the colors of the stickers of a position
+fn colors(cube: &FlatCube, stickers: &[usize]) -> Vec<Color> {
+    stickers.iter().map(|sticker| cube.stickers()[*sticker]).collect()
+}
+
+/// This function return the rotation of the stickers of a piece which gives a solved piece, and the index of this
+/// solved piece (the corners can be rotated by 3, the middle edges by 2, the wings can not)
+fn find_piece(piece: &[Color], solved: &[Vec<Color>], kind: Kind) -> Option<(usize, usize)> {
+    let rotations = if kind == Kind::Wing { 1 } else { piece.len() };
+    (0..rotations).find_map(|rotation| {
+        let mut rotated = piece.to_vec();
+        rotated.rotate_left(rotation);
+        solved.iter().position(|colors| *colors == rotated).map(|home| (rotation, home))
+    })
+}
+
+/// This function return true if a permutation is odd (it has an odd number of cycles of even length)
+fn is_odd(perm: &[usize]) -> bool {
+    let mut seen = vec![false; perm.len()];
+    let mut even_cycles: usize = 0;
+    for start in 0..perm.len() {
+        let mut length: usize = 0;
+        let mut i = start;
+        while !seen[i] {
+            seen[i] = true;
+            i = perm[i];
+            length += 1;
+        }
+        if length > 0 && length.is_multiple_of(2) {
+            even_cycles += 1;
+        }
+    }
+    !even_cycles.is_multiple_of(2)
+}
+
+/// This function return true if a vector of bits is a sum of some vectors (Gaussian elimination over GF(2))
+fn in_span(vectors: &[Vec<bool>], target: &[bool]) -> bool {
+    let mut basis: Vec<Vec<bool>> = Vec::new();
+    let reduce = |basis: &[Vec<bool>], vector: &[bool]| {
+        let mut vector = vector.to_vec();
+        for row in basis {
+            let pivot = row.iter().position(|bit| *bit).unwrap();
+            if vector[pivot] {
+                vector.iter_mut().zip(row).for_each(|(bit, other)| *bit ^= other);
+            }
+        }
+        vector
+    };
+    for vector in vectors {
+        let reduced = reduce(&basis, vector);
+        if reduced.iter().any(|bit| *bit) {
+            basis.push(reduced);
+        }
+    }
+    reduce(&basis, target).iter().all(|bit| !bit)
+}
+
+/// This function check that a NxN cube can be reached from the solved cube by some moves.
+/// Any size is checked (see the header of this file).
+///
+/// # Args
+/// * 'cube' - the cube to check
+/// * 'scheme' - the colors of the faces of the solved cube
+pub fn validate(cube: &Cube, scheme: &ColorScheme) -> Result<(), ValidationError> {
+    let size = cube.get_size();
+    let state = FlatCube::from(cube);
+    let solved = FlatCube::new_solved(size, scheme);
+
+    for color in state.stickers() {
+        if scheme.face(*color).is_none() {
+            return Err(ValidationError::Colors(*color));
+        }
+    }
+    for color in solved.stickers().iter().step_by((size * size).max(1)) {
+        if state.stickers().iter().filter(|c| *c == color).count() != size * size {
+            return Err(ValidationError::Colors(*color));
+        }
+    }
+    if size < 2 {
+        return Ok(());
+    }
+
+    let table = MoveTable::new(size);
+    let orbits = orbits(&table);
+    let corners = orbits.iter().find(|orbit| orbit.kind == Kind::Corner).unwrap();
+    let solved_corners: Vec<Vec<Color>> = corners.positions.iter().map(|stickers| colors(&solved, stickers)).collect();
+
+    // The fixed centers are turned as on the solved cube if they make real corners
+    if !size.is_multiple_of(2) {
+        let middle = size / 2;
+        for stickers in &corners.positions {
+            let centers: Vec<Color> = stickers.iter().map(|s| state.get_sticker(s / (size * size), middle, middle)).collect();
+            if find_piece(&centers, &solved_corners, Kind::Corner).is_none() {
+                return Err(ValidationError::Centers);
+            }
+        }
+    }
+
+    // The parity of each orbit of distinct pieces, for the cube then for each move
+    let mut parities = Vec::new();
+    let mut move_parities = vec![Vec::new(); table.slices().count()];
+    for orbit in &orbits {
+        let solved_pieces: Vec<Vec<Color>> = orbit.positions.iter().map(|stickers| colors(&solved, stickers)).collect();
+        let pieces: Vec<Vec<Color>> = orbit.positions.iter().map(|stickers| colors(&state, stickers)).collect();
+        let distinct = (1..solved_pieces.len()).all(|i| !solved_pieces[..i].contains(&solved_pieces[i]));
+
+        if orbit.kind == Kind::Center && !distinct {
+            let mut expected: Vec<Color> = solved_pieces.concat();
+            let mut found: Vec<Color> = pieces.concat();
+            expected.sort_by_key(|color| scheme.face(*color).map(|face| face as usize));
+            found.sort_by_key(|color| scheme.face(*color).map(|face| face as usize));
+            if expected != found {
+                return Err(ValidationError::Centers);
+            }
+            continue;
+        }
+
+        let error = match orbit.kind {
+            Kind::Corner => ValidationError::Corners,
+            Kind::Midge | Kind::Wing => ValidationError::Edges,
+            Kind::Center => ValidationError::Centers,
+        };
+        let mut homes = Vec::new();
+        let mut twist = 0;
+        for piece in &pieces {
+            let (rotation, home) = find_piece(piece, &solved_pieces, orbit.kind).ok_or(error)?;
+            if homes.contains(&home) {
+                return Err(error);
+            }
+            homes.push(home);
+            twist += rotation;
+        }
+        match orbit.kind {
+            Kind::Corner if twist % 3 != 0 => return Err(ValidationError::CornerTwist),
+            Kind::Midge if !twist.is_multiple_of(2) => return Err(ValidationError::EdgeFlip),
+            _ => (),
+        }
+        parities.push(is_odd(&homes));
+
+        // The position where each move sends the piece of each position
+        for (perm, parities) in table.slices().zip(move_parities.iter_mut()) {
+            let target = invert(perm);
+            let moved: Vec<usize> = orbit
+                .positions
+                .iter()
+                .map(|stickers| orbit.positions.iter().position(|other| other.contains(&target[stickers[0]])).unwrap())
+                .collect();
+            parities.push(is_odd(&moved));
+        }
+    }
+    if !in_span(&move_parities, &parities) {
+        return Err(ValidationError::Parity);
+    }
+    Ok(())
+}
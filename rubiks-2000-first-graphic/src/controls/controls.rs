@@ -0,0 +1,137 @@
+use crate::graphic_cube::gcube::{ face_move_to_slices, Move };
+use rubiks_2000_core::moves::slice_to_face_move;
+use rubiks_2000::cube::enums::Move as FaceMove;
+
+/// The axes of the cube, given by the face turned clockwise by the rotation (x like R, y like U, z like F)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+/// A turn played with the keyboard
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Turn {
+    /// A turn of a face, as in the solver
+    Face(FaceMove),
+    /// A turn of the inner slices (M like L, E like D, S like F), true for the inverse
+    Slice(Axis, bool),
+    /// A rotation of the whole cube (x, y, z), true for the inverse
+    Rotation(Axis, bool),
+    /// A quarter turn of any slice, as turned with the mouse
+    Layer(Move, usize),
+}
+
+impl Turn {
+    /// This function return the turn of a quarter turn of a slice, with its name in notation when it has one
+    ///
+    /// # Args
+    /// * 'mov' - the type of move
+    /// * 'n' - the slice concerned by the move, numbered as in the Cube
+    /// * 'size' - the size of the cube
+    pub fn from_slice(mov: Move, n: usize, size: usize) -> Self {
+        if let Some(mv) = slice_to_face_move(mov, n, size) {
+            return Turn::Face(mv);
+        }
+        if size != 3 {
+            return Turn::Layer(mov, n);
+        }
+        // The middle slice of the 3x3x3 cube
+        match mov {
+            Move::Vp => Turn::Slice(Axis::X, false),
+            Move::V => Turn::Slice(Axis::X, true),
+            Move::H => Turn::Slice(Axis::Y, false),
+            Move::Hp => Turn::Slice(Axis::Y, true),
+            Move::L => Turn::Slice(Axis::Z, false),
+            Move::Lp => Turn::Slice(Axis::Z, true),
+        }
+    }
+
+    /// This function return the turn which undoes this one
+    pub fn inverse(self) -> Self {
+        match self {
+            Turn::Face(mv) => Turn::Face(mv.move_inv()),
+            Turn::Slice(axis, prime) => Turn::Slice(axis, !prime),
+            Turn::Rotation(axis, prime) => Turn::Rotation(axis, !prime),
+            Turn::Layer(mov, n) => Turn::Layer(mov.inverse(), n),
+        }
+    }
+
+    /// This function return the slice moves of the turn on a cube of size 'size'
+    pub fn slices(self, size: usize) -> Vec<(Move, usize)> {
+        let (mov, layers) = match self {
+            Turn::Face(mv) => return face_move_to_slices(mv, size),
+            Turn::Layer(mov, n) => return vec![(mov, n)],
+            // The inner slices turn like the faces L, D and F
+            Turn::Slice(Axis::X, _) => (Move::Vp, 1..size - 1),
+            Turn::Slice(Axis::Y, _) => (Move::H, 1..size - 1),
+            Turn::Slice(Axis::Z, _) => (Move::L, 1..size - 1),
+            // The rotations turn like the faces R, U and F
+            Turn::Rotation(Axis::X, _) => (Move::V, 0..size),
+            Turn::Rotation(Axis::Y, _) => (Move::Hp, 0..size),
+            Turn::Rotation(Axis::Z, _) => (Move::L, 0..size),
+        };
+        let prime = matches!(self, Turn::Slice(_, true) | Turn::Rotation(_, true));
+        let mov = if prime { mov.inverse() } else { mov };
+        layers.map(|n| (mov, n)).collect()
+    }
+
+    /// This function return the turn in notation (R, U', M, x' ...)
+    pub fn to_notation(self) -> String {
+        let (name, prime) = match self {
+            Turn::Face(mv) => return mv.to_notation(),
+            Turn::Layer(mov, n) => return format!("{:?}{}", mov, n),
+            Turn::Slice(Axis::X, prime) => ("M", prime),
+            Turn::Slice(Axis::Y, prime) => ("E", prime),
+            Turn::Slice(Axis::Z, prime) => ("S", prime),
+            Turn::Rotation(Axis::X, prime) => ("x", prime),
+            Turn::Rotation(Axis::Y, prime) => ("y", prime),
+            Turn::Rotation(Axis::Z, prime) => ("z", prime),
+        };
+        if prime {
+            format!("{}'", name)
+        } else {
+            String::from(name)
+        }
+    }
+}
+
+/// The turns played with the keyboard, to undo and redo them
+#[derive(Default)]
+pub struct History {
+    done: Vec<Turn>,
+    undone: Vec<Turn>,
+}
+
+impl History {
+    /// This function create an empty history
+    pub fn new() -> Self {
+        History { done: Vec::new(), undone: Vec::new() }
+    }
+
+    /// This function record a new turn (the turns undone can not be redone anymore)
+    pub fn push(&mut self, turn: Turn) {
+        self.done.push(turn);
+        self.undone.clear();
+    }
+
+    /// This function return the turn which undoes the last turn, None if there is nothing to undo
+    pub fn undo(&mut self) -> Option<Turn> {
+        let turn = self.done.pop()?;
+        self.undone.push(turn);
+        Some(turn.inverse())
+    }
+
+    /// This function return the last turn undone, None if there is nothing to redo
+    pub fn redo(&mut self) -> Option<Turn> {
+        let turn = self.undone.pop()?;
+        self.done.push(turn);
+        Some(turn)
+    }
+
+    /// This function return the turns played, in notation
+    pub fn to_notation(&self) -> String {
+        self.done.iter().map(|turn| turn.to_notation()).collect::<Vec<_>>().join(" ")
+    }
+}
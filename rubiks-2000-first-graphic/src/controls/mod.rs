@@ -0,0 +1,2 @@
+pub mod controls;
+pub mod picking;
@@ -0,0 +1,163 @@
+use crate::graphic_cube::gcube::{ Move, SIZE };
+use na::{ Point3, Vector3 };
+
+/// The length of a drag before its direction is chosen
+pub const DRAG_START: f32 = 0.3;
+/// The length of a drag which turns a slice by a quarter turn
+pub const DRAG_QUARTER: f32 = 2.5;
+
+/// A ray from the camera, in the coordinates of the cube
+#[derive(Clone, Copy, Debug)]
+pub struct Ray {
+    pub origin: Point3<f32>,
+    pub direction: Vector3<f32>,
+}
+
+/// The sticker under the mouse
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hit {
+    /// The axis of the normal of the face (0 for x, 1 for y, 2 for z)
+    pub axis: usize,
+    /// The side of the face along its axis (1.0 or -1.0)
+    pub side: f32,
+    /// The position of the cublet (as Cublet::position)
+    pub position: (usize, usize, usize),
+    /// The point of the face under the mouse
+    pub point: Point3<f32>,
+}
+
+/// A mouse drag on the cube, which turns the slice under the sticker clicked
+#[derive(Clone, Copy, Debug)]
+pub struct Drag {
+    hit: Hit,
+    size: usize,
+    /// The move, its slice and the axis of the drag, once the direction of the drag is known
+    turn: Option<(Move, usize, usize)>,
+}
+
+/// This function return the coordinate of the centers of the cublets of index 'index' along an axis
+fn center(index: usize, size: usize, gap: f32) -> f32 {
+    (index as f32 - (size - 1) as f32 / 2.0) * (SIZE + gap)
+}
+
+/// This function return the index of the cublets at the coordinate 'coord' along an axis
+fn index(coord: f32, size: usize, gap: f32) -> usize {
+    (coord / (SIZE + gap) + (size - 1) as f32 / 2.0).round().clamp(0.0, (size - 1) as f32) as usize
+}
+
+/// This function return the point where the ray crosses the plane 'axis' = 'coord', None behind the camera
+fn plane_point(ray: &Ray, axis: usize, coord: f32) -> Option<Point3<f32>> {
+    if ray.direction[axis] == 0.0 {
+        return None;
+    }
+    let t = (coord - ray.origin[axis]) / ray.direction[axis];
+    if t <= 0.0 {
+        return None;
+    }
+    Some(ray.origin + ray.direction * t)
+}
+
+/// This function find the sticker hit by a ray
+///
+/// # Args
+/// * 'ray' - the ray from the camera
+/// * 'size' - the size of the cube
+/// * 'gap' - the gap between two cublets
+///
+/// # Return
+///
+/// The nearest sticker hit, None if the ray misses the cube
+pub fn pick(ray: &Ray, size: usize, gap: f32) -> Option<Hit> {
+    let half = center(size - 1, size, gap) + SIZE / 2.0;
+    let mut best: Option<(f32, Hit)> = None;
+
+    for axis in 0..3 {
+        for side in [1.0, -1.0] {
+            // Only the faces turned to the camera can be hit
+            if ray.direction[axis] * side >= 0.0 {
+                continue;
+            }
+            let Some(point) = plane_point(ray, axis, side * half) else {
+                continue;
+            };
+            if (0..3).any(|other| other != axis && point[other].abs() > half) {
+                continue;
+            }
+            let distance = (point - ray.origin).norm();
+            if best.is_none_or(|(best_distance, _)| distance < best_distance) {
+                let coordinate = |other: usize| match other {
+                    _ if other != axis => index(point[other], size, gap),
+                    _ if side > 0.0 => size - 1,
+                    _ => 0,
+                };
+                let position = (coordinate(0), coordinate(1), coordinate(2));
+                best = Some((distance, Hit { axis, side, position, point }));
+            }
+        }
+    }
+    best.map(|(_, hit)| hit)
+}
+
+impl Drag {
+    /// This function start a drag if the ray hits the cube
+    pub fn start(ray: &Ray, size: usize, gap: f32) -> Option<Self> {
+        pick(ray, size, gap).map(|hit| Drag { hit, size, turn: None })
+    }
+
+    /// This function return the sticker where the drag started
+    pub fn hit(&self) -> Hit {
+        self.hit
+    }
+
+    /// This function follow the mouse during the drag
+    ///
+    /// # Args
+    /// * 'ray' - the ray from the camera to the mouse
+    ///
+    /// # Return
+    ///
+    /// The move, its slice (numbered as in the Cube) and the rotation as a fraction of a quarter turn,
+    /// None while the drag is too short to know its direction
+    pub fn update(&mut self, ray: &Ray) -> Option<(Move, usize, f32)> {
+        let point = plane_point(ray, self.hit.axis, self.hit.point[self.hit.axis])?;
+        let drag = point - self.hit.point;
+
+        if self.turn.is_none() {
+            // The drag follows the main direction of the mouse in the plane of the face
+            let drag_axis = (0..3)
+                .filter(|axis| *axis != self.hit.axis)
+                .max_by(|a, b| drag[*a].abs().total_cmp(&drag[*b].abs()))
+                .unwrap();
+            if drag[drag_axis].abs() < DRAG_START {
+                return None;
+            }
+
+            // A rotation around normal x drag moves the sticker along the drag
+            let mut normal = Vector3::zeros();
+            normal[self.hit.axis] = self.hit.side;
+            let rotation = normal.cross(&Vector3::ith(drag_axis, 1.0));
+            let axis = 3 - self.hit.axis - drag_axis;
+            let positive = rotation[axis] > 0.0;
+            let mov = match (axis, positive) {
+                (0, true) => Move::V,
+                (0, false) => Move::Vp,
+                (1, true) => Move::H,
+                (1, false) => Move::Hp,
+                (_, true) => Move::L,
+                (_, false) => Move::Lp,
+            };
+
+            // The positions of the cublets and the slices of the Cube are numbered the other way
+            let layer = [self.hit.position.0, self.hit.position.1, self.hit.position.2][axis];
+            self.turn = Some((mov, self.size - 1 - layer, drag_axis));
+        }
+
+        let (mov, n, drag_axis) = self.turn?;
+        Some((mov, n, drag[drag_axis] / DRAG_QUARTER))
+    }
+}
+
+/// This function return the number of quarter turns nearest to a rotation
+pub fn snap(fraction: f32) -> i32 {
+    fraction.round() as i32
+}
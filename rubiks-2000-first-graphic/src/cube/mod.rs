@@ -1 +1 @@
-pub mod cube;
\ No newline at end of file
+pub use rubiks_2000_core::cube;
@@ -0,0 +1,119 @@
+use crate::cube::cube::{ Color, Cube };
+use rubiks_2000::cube::face_cube::FaceCube;
+use rubiks_2000::cube::formats::FACE_LETTERS;
+use rubiks_2000_core::convert::{ to_cubie_cube, to_face_cube, ConvertError, FACE_INDEX };
+use rubiks_2000_core::scheme::{ ColorScheme, SchemeError };
+use std::fmt;
+
+/// The colors which can be painted, selected with the keys 1 to 6: the colors of the faces U, R, F, D, L and B
+/// of the solved Cube (see ColorScheme::DELTA). They are named by their face, as the theme draws the faces.
+pub const PALETTE: [Color; 6] = [Color::Blue, Color::Orange, Color::White, Color::Green, Color::Red, Color::Yellow];
+
+/// The state of the paint mode: the stickers clicked take the color selected in the palette.
+/// The painted cube is the Cube of the Gcube, so the turns still work while painting.
+pub struct Editor {
+    selected: usize,
+}
+
+/// The reasons why a painted cube is not a state of the 3x3x3 cube
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditError {
+    /// A color is not on exactly one sticker of each cubie
+    Count(Color, usize),
+    /// Two centers have the same color
+    Scheme(SchemeError),
+    /// The stickers do not form a solvable cube
+    Convert(ConvertError),
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EditError::Count(color, count) => {
+                write!(f, "The color of the face {} is on {} stickers instead of 9", face_letter(*color), count)
+            }
+            EditError::Scheme(error) => write!(f, "{}", error),
+            EditError::Convert(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for EditError {}
+
+impl Editor {
+    /// This function create an editor, with the first color of the palette selected
+    pub fn new() -> Self {
+        Editor { selected: 0 }
+    }
+
+    /// This function select a color of the palette (the index is taken modulo the size of the palette)
+    pub fn select(&mut self, index: usize) {
+        self.selected = index % PALETTE.len();
+    }
+
+    /// This function return the color selected
+    pub fn color(&self) -> Color {
+        PALETTE[self.selected]
+    }
+
+    /// This function return the entries of the palette as text (the key and the face), with their color.
+    /// The color selected is between brackets.
+    pub fn palette(&self) -> Vec<(String, Color)> {
+        PALETTE
+            .iter()
+            .enumerate()
+            .map(|(i, color)| match i == self.selected {
+                true => (format!("[{} {}]", i + 1, face_letter(*color)), *color),
+                false => (format!("{} {}", i + 1, face_letter(*color)), *color),
+            })
+            .collect()
+    }
+}
+
+impl Default for Editor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// This function return the letter of the face of a color of the palette, '?' for the other colors
+pub fn face_letter(color: Color) -> char {
+    PALETTE.iter().position(|c| *c == color).map_or('?', |index| FACE_LETTERS[index])
+}
+
+/// This function return the number of stickers of each color of the palette
+pub fn counts(cube: &Cube) -> [usize; 6] {
+    let size = cube.get_size();
+    let mut counts = [0; 6];
+    for face in 0..6 {
+        for row in 0..size {
+            for col in 0..size {
+                let color = cube.get_sticker(face, row, col);
+                if let Some(index) = PALETTE.iter().position(|c| *c == color) {
+                    counts[index] += 1;
+                }
+            }
+        }
+    }
+    counts
+}
+
+/// This function check that a painted 3x3x3 cube is a solvable state.
+/// The color scheme is given by the centers, so any orientation of the cube can be painted.
+///
+/// # Return
+///
+/// The state for the solver, or the first problem found
+pub fn check_state(cube: &Cube) -> Result<FaceCube, EditError> {
+    if cube.get_size() != 3 {
+        return Err(EditError::Convert(ConvertError::Size(cube.get_size())));
+    }
+    for (color, count) in PALETTE.iter().zip(counts(cube)) {
+        if count != 9 {
+            return Err(EditError::Count(*color, count));
+        }
+    }
+    let scheme = ColorScheme::new(FACE_INDEX.map(|face| cube.get_sticker(face, 1, 1))).map_err(EditError::Scheme)?;
+    to_cubie_cube(cube, &scheme).map_err(EditError::Convert)?;
+    to_face_cube(cube, &scheme).map_err(EditError::Convert)
+}
@@ -0,0 +1,166 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use super::gcube::Move;
+
+/// The default duration of the animation of a quarter turn, in seconds
+pub const DEFAULT_DURATION: f32 = 0.5;
+
+/// The speed of the animation of a move along its duration
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Easing {
+    /// Constant speed
+    Linear,
+    /// Slow at the start and at the end
+    EaseInOut,
+    /// Fast at the start, slow at the end
+    EaseOut,
+}
+
+impl Easing {
+    /// This function return the progress of the move (0 to 1) at a fraction 't' (0 to 1) of its duration
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+        }
+    }
+}
+
+/// A step of the animation of a move during one frame
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Step {
+    pub mov: Move,
+    pub n: usize,
+    /// The rotation to apply during this frame, as a fraction of a quarter turn
+    pub fraction: f32,
+    /// True if the move is finished after this step
+    pub finished: bool,
+}
+
+/// The animation of the current move
+#[derive(Clone, Copy, Debug)]
+struct Current {
+    mov: Move,
+    n: usize,
+    /// The time spent on the move, in seconds
+    time: f32,
+    /// The progress of the move already applied (0 to 1)
+    progress: f32,
+}
+
+/// A queue of moves animated one after the other, frame by frame
+pub struct AnimationQueue {
+    pending: VecDeque<(Move, usize)>,
+    current: Option<Current>,
+    /// The duration of the animation of a quarter turn
+    pub duration: Duration,
+    pub easing: Easing,
+    /// The speed of the animations (2.0 is twice faster)
+    speed: f32,
+}
+
+impl AnimationQueue {
+    /// This function create an empty queue
+    pub fn new() -> Self {
+        AnimationQueue {
+            pending: VecDeque::new(),
+            current: None,
+            duration: Duration::from_secs_f32(DEFAULT_DURATION),
+            easing: Easing::EaseInOut,
+            speed: 1.0,
+        }
+    }
+
+    /// This function add a move at the end of the queue
+    pub fn push(&mut self, mov: Move, n: usize) {
+        self.pending.push_back((mov, n));
+    }
+
+    /// This function return true if a move is animated or waiting
+    pub fn is_busy(&self) -> bool {
+        self.current.is_some() || !self.pending.is_empty()
+    }
+
+    /// This function return the number of moves animated or waiting
+    pub fn len(&self) -> usize {
+        self.pending.len() + self.current.is_some() as usize
+    }
+
+    /// This function return true if no move is animated or waiting
+    pub fn is_empty(&self) -> bool {
+        !self.is_busy()
+    }
+
+    /// This function return the speed of the animations
+    pub fn get_speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// This function change the speed of the animations, even during a move
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.max(0.01);
+    }
+
+    /// This function advance the animations by the time of a frame
+    ///
+    /// # Args
+    /// * 'dt' - the time since the last frame
+    ///
+    /// # Return
+    ///
+    /// The steps to apply during this frame (several moves can end in the same frame)
+    pub fn update(&mut self, dt: Duration) -> Vec<Step> {
+        let duration = self.duration.as_secs_f32();
+        let mut remaining = dt.as_secs_f32() * self.speed;
+        let mut steps = Vec::new();
+
+        loop {
+            if self.current.is_none() {
+                let Some((mov, n)) = self.pending.pop_front() else {
+                    break;
+                };
+                self.current = Some(Current { mov, n, time: 0.0, progress: 0.0 });
+            }
+            let current = self.current.as_mut().unwrap();
+
+            let used = remaining.min(duration - current.time);
+            current.time += used;
+            remaining -= used;
+            let finished = current.time >= duration;
+            let progress = if finished { 1.0 } else { self.easing.apply(current.time / duration) };
+            steps.push(Step { mov: current.mov, n: current.n, fraction: progress - current.progress, finished });
+            current.progress = progress;
+
+            if !finished {
+                break;
+            }
+            self.current = None;
+        }
+        steps
+    }
+
+    /// This function finish all the moves at once
+    ///
+    /// # Return
+    ///
+    /// The steps which finish the moves
+    pub fn finish_all(&mut self) -> Vec<Step> {
+        let mut steps = Vec::new();
+        if let Some(current) = self.current.take() {
+            steps.push(Step { mov: current.mov, n: current.n, fraction: 1.0 - current.progress, finished: true });
+        }
+        for (mov, n) in self.pending.drain(..) {
+            steps.push(Step { mov, n, fraction: 1.0, finished: true });
+        }
+        steps
+    }
+}
+
+impl Default for AnimationQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
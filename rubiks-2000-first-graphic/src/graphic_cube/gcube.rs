@@ -2,31 +2,33 @@ use std::{
     borrow::BorrowMut,
     cell::RefCell,
     rc::Rc,
-    time::{Duration, Instant},
+    time::Duration,
     usize,
 };
 
-use crate::cube::cube::Cube;
+use super::animation::{AnimationQueue, Easing, Step};
+use super::layout::sticker_cublet;
+use crate::controls::picking::snap;
+use crate::cube::cube::{Color, Cube};
 use kiss3d::{
-    nalgebra::{Isometry3, Translation3, UnitQuaternion},
+    nalgebra::{Isometry3, Translation3, UnitQuaternion, Vector3},
     scene::SceneNode,
     window::Window,
 };
 use rand::Rng;
 
-const SIZE: f32 = 1.0;
-const OFFSET: f32 = 0.2;
-const ANIMATION_TIME: f32 = 2.0;
-
-#[derive(PartialEq)]
-pub enum Move {
-    V,
-    Vp,
-    H,
-    Hp,
-    L,
-    Lp,
-}
+/// The size of a cublet
+pub const SIZE: f32 = 1.0;
+/// The color of the stickers which have no color (Color::Empty)
+const EMPTY_COLOR: (f32, f32, f32) = (0.3, 0.3, 0.3);
+
+/// The moves of the graphic cube are the slice moves of the Cube, so that both stay in sync
+pub use rubiks_2000_core::moves::{ face_move_to_slices, SliceMove as Move };
+use rubiks_2000::cube::enums::Move as FaceMove;
+use rubiks_2000::cube::face_cube::FaceCube;
+use rubiks_2000_core::convert::to_nxn;
+use rubiks_2000::render::theme::{Rgb, Theme};
+use rubiks_2000_core::scheme::ColorScheme;
 
 #[derive(Clone)]
 struct Cublet {
@@ -41,7 +43,8 @@ struct Cublet {
 }
 
 impl Cublet {
-    pub fn new() -> Self {
+    /// This function create a cublet with all its faces of the body color
+    pub fn new(body: Rgb) -> Self {
         let mut cubelet = SceneNode::new_empty();
 
         // Create faces
@@ -61,13 +64,14 @@ impl Cublet {
         top_face.set_local_translation(Translation3::new(0.0, half_size, 0.0));
         bottom_face.set_local_translation(Translation3::new(0.0, -half_size, 0.0));
 
-        // Set faces color to blac
-        back_face.set_color(0.0, 0.0, 0.0);
-        front_face.set_color(0.0, 0.0, 0.0);
-        left_face.set_color(0.0, 0.0, 0.0);
-        right_face.set_color(0.0, 0.0, 0.0);
-        top_face.set_color(0.0, 0.0, 0.0);
-        bottom_face.set_color(0.0, 0.0, 0.0);
+        // Set faces color to the body color
+        let (r, g, b) = body.to_unit();
+        back_face.set_color(r, g, b);
+        front_face.set_color(r, g, b);
+        left_face.set_color(r, g, b);
+        right_face.set_color(r, g, b);
+        top_face.set_color(r, g, b);
+        bottom_face.set_color(r, g, b);
 
         // Apply face rotation
         back_face.set_local_rotation(UnitQuaternion::from_euler_angles(0.0, 0.0, 0.0));
@@ -118,24 +122,59 @@ impl Cublet {
     pub fn set_position(&mut self, x: usize, y: usize, z: usize) {
         self.position = (x, y, z);
     }
+
+    /// This function return the face of the cublet which is turned toward a side of the cube
+    ///
+    /// # Args
+    /// * 'axis' - the axis of the side (0 for x, 1 for y, 2 for z)
+    /// * 'side' - the direction along the axis (1.0 or -1.0)
+    fn face_toward(&mut self, axis: usize, side: f32) -> &mut SceneNode {
+        let mut direction = Vector3::zeros();
+        direction[axis] = side;
+        // The direction in the coordinates of the cublet, before its rotations
+        let local = self.node.data().local_transformation().rotation.inverse() * direction;
+        let local_axis = (0..3).max_by(|a, b| local[*a].abs().total_cmp(&local[*b].abs())).unwrap();
+        match (local_axis, local[local_axis] > 0.0) {
+            (0, true) => &mut self.left_face,
+            (0, false) => &mut self.right_face,
+            (1, true) => &mut self.top_face,
+            (1, false) => &mut self.bottom_face,
+            (_, true) => &mut self.back_face,
+            (_, false) => &mut self.front_face,
+        }
+    }
 }
 
 pub struct Gcube {
     cube: Cube,
     cublets: Vec<Cublet>,
+    animations: AnimationQueue,
+    theme: Theme,
+    /// The slice turned by the mouse and its rotation (as a fraction of a quarter turn)
+    dragged: Option<(Move, usize, f32)>,
 }
 
 impl Gcube {
-    /// This function crate a new Gcube
+    /// This function crate a new Gcube, colored as the stickers of a Cube with the colors of the first viewer
     ///
     /// # Args:
-    /// * '_cube' - the Cube object (to talk with solver)
+    /// * '_cube' - the Cube object (to talk with solver), in any state
     /// * 'window' - the window in which display the 3D object
     ///
     /// ## Warning:
     /// The size of the cube will depend on the size of the '_cube' parameter!
     ///
     pub fn new(_cube: Cube, window: &mut Window) -> Self {
+        Self::with_theme(_cube, Theme::DELTA, window)
+    }
+
+    /// This function create a new Gcube, colored as the stickers of a Cube with the colors of a theme
+    ///
+    /// # Args:
+    /// * '_cube' - the Cube object, in any state
+    /// * 'theme' - the colors of the faces, of the body and the gap between the cublets
+    /// * 'window' - the window in which display the 3D object
+    pub fn with_theme(_cube: Cube, theme: Theme, window: &mut Window) -> Self {
         let mut _node = window.add_group();
         let s = (_cube.get_size() as i32) / 2;
         let mut _cublets = Vec::new();
@@ -156,14 +195,15 @@ impl Gcube {
                         continue;
                     }
                     let fz = k as f32;
-                    let mut cublet = Cublet::new();
+                    let mut cublet = Cublet::new(theme.body);
+                    let gap = theme.gap;
                     let translation = if !pair {
-                        Isometry3::translation(fx * OFFSET + fx, fy * OFFSET + fy, fz * OFFSET + fz)
+                        Isometry3::translation(fx * gap + fx, fy * gap + fy, fz * gap + fz)
                     } else {
                         Isometry3::translation(
-                            (fx - fx.signum() * 0.5) * OFFSET + (fx - fx.signum() * 0.5),
-                            (fy - fy.signum() * 0.5) * OFFSET + (fy - fy.signum() * 0.5),
-                            (fz - fz.signum() * 0.5) * OFFSET + (fz - fz.signum() * 0.5),
+                            (fx - fx.signum() * 0.5) * gap + (fx - fx.signum() * 0.5),
+                            (fy - fy.signum() * 0.5) * gap + (fy - fy.signum() * 0.5),
+                            (fz - fz.signum() * 0.5) * gap + (fz - fz.signum() * 0.5),
                         )
                     };
                     cublet.node.set_local_transformation(translation);
@@ -172,21 +212,6 @@ impl Gcube {
                         (j + s - (pair && j > 0) as i32) as usize,
                         (k + s - (pair && k > 0) as i32) as usize,
                     );
-                    if k == s {
-                        cublet.back_face.set_color(1.0, 1.0, 0.0);
-                    } else if k == -s {
-                        cublet.front_face.set_color(1.0, 1.0, 1.0)
-                    }
-                    if i == s {
-                        cublet.left_face.set_color(1.0, 0.0, 0.0)
-                    } else if i == -s {
-                        cublet.right_face.set_color(1.0, 0.5, 0.0)
-                    }
-                    if j == s {
-                        cublet.top_face.set_color(0.0, 0.0, 1.0)
-                    } else if j == -s {
-                        cublet.bottom_face.set_color(0.0, 1.0, 0.0);
-                    }
                     let rc_child = Rc::new(RefCell::new(cublet));
                     let child_node = Rc::clone(&rc_child).borrow().node.clone();
                     let cublet = Rc::clone(&rc_child).borrow().clone();
@@ -196,14 +221,42 @@ impl Gcube {
             }
         }
 
-        Gcube {
+        let mut gcube = Gcube {
             cube: _cube,
-
             cublets: _cublets,
+            animations: AnimationQueue::new(),
+            theme,
+            dragged: None,
+        };
+
+        // The outer faces of the cublets take the colors of the stickers of the Cube
+        let size = gcube.cube.get_size();
+        for face in 0..6 {
+            for row in 0..size {
+                for col in 0..size {
+                    let color = gcube.cube.get_sticker(face, row, col);
+                    gcube.paint_sticker(face, row, col, color);
+                }
+            }
         }
+        gcube
+    }
+
+    /// This function create a new 3x3x3 Gcube in the state of a facelet cube of the solver
+    ///
+    /// # Args:
+    /// * 'face_cube' - the state of the cube
+    /// * 'scheme' - the color of each face of the solver
+    /// * 'window' - the window in which display the 3D object
+    pub fn from_face_cube(face_cube: &FaceCube, scheme: &ColorScheme, window: &mut Window) -> Self {
+        Self::new(to_nxn(face_cube, scheme), window)
     }
 
-    pub fn shuffle(&mut self, window: &mut Window, moves: u32) {
+    /// This function queue random moves
+    ///
+    /// # Args
+    /// * 'moves' - the number of moves
+    pub fn shuffle(&mut self, moves: u32) {
         let mut rng = rand::thread_rng();
 
         for _ in 0..moves {
@@ -219,40 +272,168 @@ impl Gcube {
                 _ => Move::Vp,
             };
 
-            self.apply_move(window, mov, n);
+            self.apply_move(mov, n);
         }
     }
 
     fn adjust_cublet_positions(&mut self) {
-        let precision = 0.1;
+        // The centers of the cublets are on a grid of step SIZE + gap, centered on the cube
+        let step = SIZE + self.theme.gap;
+        let center = (self.cube.get_size() - 1) as f32 / 2.0;
+        let snap = |coord: f32| ((coord / step + center).round() - center) * step;
         for i in 0..self.cublets.len() {
             let cublet = self.cublets[i].borrow_mut();
             let x = cublet.node.data().local_transformation().translation.x;
             let y = cublet.node.data().local_transformation().translation.y;
             let z = cublet.node.data().local_transformation().translation.z;
-            let translation = Translation3::new(
-                (x / precision).round() * precision,
-                (y / precision).round() * precision,
-                (z / precision).round() * precision,
-            );
+            let translation = Translation3::new(snap(x), snap(y), snap(z));
             cublet.node.set_local_translation(translation);
         }
     }
 
-    /// This function make the graphic cube execute move with animation
+    /// This function queue a move, animated by the next calls to 'update'
     ///
     /// # Args
-    /// * 'window' - a reference to the window where the cube is displayed
     /// * 'mov' - the type of move
-    /// * 'n' - the stripe concerned by the move (depending on the type)
+    /// * 'n' - the stripe concerned by the move (depending on the type), numbered as in the Cube
+    pub fn apply_move(&mut self, mov: Move, n: usize) {
+        self.animations.push(mov, n);
+    }
+
+    /// This function queue a move of the solver (a half turn is animated as two quarter turns)
+    pub fn apply_face_move(&mut self, mv: FaceMove) {
+        for (mov, n) in face_move_to_slices(mv, self.cube.get_size()) {
+            self.apply_move(mov, n);
+        }
+    }
+
+    /// This function execute a move of the solver at once, without animation.
+    /// The moves still in the queue are finished first.
+    pub fn apply_face_move_instant(&mut self, mv: FaceMove) {
+        self.finish_animations();
+        for (mov, n) in face_move_to_slices(mv, self.cube.get_size()) {
+            self.rotate_stripe(mov, n, 1.0);
+            self.end_move(mov, n);
+        }
+    }
+
+    /// This function animate the queued moves, it must be called once per frame
     ///
-    /// # Warning:
-    /// The time of the animation will depend on the global constant 'ANIMATION_DURATION'
-    pub fn apply_move(&mut self, window: &mut Window, mov: Move, n: usize) {
-        let animation_duration = Duration::from_secs_f32(ANIMATION_TIME);
-        let start_time = Instant::now();
-        let mut prev = 0.0;
-        let s = self.cube.get_size() - 1;
+    /// # Args
+    /// * 'dt' - the time since the last frame
+    pub fn update(&mut self, dt: Duration) {
+        // The queued moves wait for the end of a drag
+        if self.dragged.is_some() {
+            return;
+        }
+        for step in self.animations.update(dt) {
+            self.apply_step(step);
+        }
+    }
+
+    /// This function finish at once the move animated and the moves queued
+    pub fn finish_animations(&mut self) {
+        for step in self.animations.finish_all() {
+            self.apply_step(step);
+        }
+    }
+
+    /// This function return true if a move is animated or queued
+    pub fn is_animating(&self) -> bool {
+        self.animations.is_busy()
+    }
+
+    /// This function change the duration of the animation of a quarter turn
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.animations.duration = duration;
+    }
+
+    /// This function change the easing of the animations
+    pub fn set_easing(&mut self, easing: Easing) {
+        self.animations.easing = easing;
+    }
+
+    /// This function return the speed of the animations
+    pub fn get_speed(&self) -> f32 {
+        self.animations.get_speed()
+    }
+
+    /// This function change the speed of the animations (2.0 is twice faster), even during a move
+    pub fn set_speed(&mut self, speed: f32) {
+        self.animations.set_speed(speed);
+    }
+
+    /// This function turn a slice with the mouse: the slice follows the drag until it is released.
+    /// The moves queued are finished first.
+    ///
+    /// # Args
+    /// * 'mov' - the type of move
+    /// * 'n' - the stripe concerned by the move, numbered as in the Cube
+    /// * 'fraction' - the rotation since the start of the drag, as a fraction of a quarter turn
+    pub fn drag_stripe(&mut self, mov: Move, n: usize, fraction: f32) {
+        let previous = match self.dragged {
+            Some((_, _, previous)) => previous,
+            None => {
+                self.finish_animations();
+                0.0
+            }
+        };
+        self.rotate_stripe(mov, n, fraction - previous);
+        self.dragged = Some((mov, n, fraction));
+    }
+
+    /// This function end a drag: the slice snaps to the nearest quarter turn
+    ///
+    /// # Return
+    ///
+    /// The quarter turns played by the drag, as moves of the stripe (empty if the slice went back)
+    pub fn release_stripe(&mut self) -> Vec<(Move, usize)> {
+        let Some((mov, n, fraction)) = self.dragged.take() else {
+            return Vec::new();
+        };
+        let quarters = snap(fraction);
+        self.rotate_stripe(mov, n, quarters as f32 - fraction);
+
+        let played = if quarters < 0 { mov.inverse() } else { mov };
+        let moves = vec![(played, n); quarters.unsigned_abs() as usize];
+        for (mov, n) in &moves {
+            self.end_move(*mov, *n);
+        }
+        moves
+    }
+
+    fn apply_step(&mut self, step: Step) {
+        self.rotate_stripe(step.mov, step.n, step.fraction);
+        if step.finished {
+            self.end_move(step.mov, step.n);
+        }
+    }
+
+    /// This function return the coordinate of the cublets which gives their stripe for a type of move
+    fn stripe_coordinate(mov: Move) -> fn((usize, usize, usize)) -> usize {
+        match mov {
+            Move::H | Move::Hp => |(_, y, _)| y,
+            Move::L | Move::Lp => |(_, _, z)| z,
+            Move::V | Move::Vp => |(x, _, _)| x,
+        }
+    }
+
+    /// This function return the stripe of the cublets concerned by a move.
+    /// The Cube numbers its stripes from the left, the top and the back, while the positions of the
+    /// cublets grow to the left, the top and the back: the stripes are numbered the other way
+    fn stripe(&self, n: usize) -> usize {
+        self.cube.get_size() - 1 - n
+    }
+
+    /// This function turn the cublets of a stripe, without changing their positions
+    ///
+    /// # Args
+    /// * 'mov' - the type of move
+    /// * 'n' - the stripe concerned by the move, numbered as in the Cube
+    /// * 'fraction' - the rotation, as a fraction of a quarter turn
+    fn rotate_stripe(&mut self, mov: Move, n: usize, fraction: f32) {
+        let stripe = self.stripe(n);
+        let f_move = Self::stripe_coordinate(mov);
 
         // Determine factors (the axe of the rotation)
         let (roll_fact, pitch_fact, yaw_fact) = match mov {
@@ -263,91 +444,84 @@ impl Gcube {
             Move::L => (0.0, 0.0, 1.0),
             Move::Lp => (0.0, 0.0, -1.0),
         };
-
-        // Functions to determine which cublet to move depending of the move type
-        let f_v_move = |(x, _, _): (usize, usize, usize)| x;
-        let f_h_move = |(_, y, _): (usize, usize, usize)| y;
-        let f_l_move = |(_, _, z): (usize, usize, usize)| z;
-
-        // Functions to determine which coordinates to update depending of the move type
-        let f_h_coord = |cub: &mut Cublet| {
-            let (x, y, z) = cub.position;
-            cub.set_position(z, y, s - x);
-        };
-        let f_l_coord = |cub: &mut Cublet| {
-            let (x, y, z) = cub.position;
-            cub.set_position(s - y, x, z);
-        };
-        let f_v_coord = |cub: &mut Cublet| {
-            let (x, y, z) = cub.position;
-            cub.set_position(x, s - z, y);
-        };
-        let f_hp_coord = |cub: &mut Cublet| {
-            let (x, y, z) = cub.position;
-            cub.set_position(s - z, y, x);
-        };
-        let f_lp_coord = |cub: &mut Cublet| {
-            let (x, y, z) = cub.position;
-            cub.set_position(y, s - x, z);
-        };
-        let f_vp_coord = |cub: &mut Cublet| {
-            let (x, y, z) = cub.position;
-            cub.set_position(x, z, s - y);
-        };
-
-        let f_move = if mov == Move::H || mov == Move::Hp {
-            f_h_move
-        } else if mov == Move::L || mov == Move::Lp {
-            f_l_move
-        } else {
-            f_v_move
-        };
-
-        // Animation loop
-        while window.render() {
-            let elapsed_time = start_time.elapsed();
-            let delta = (elapsed_time.as_secs_f32() - prev) / animation_duration.as_secs_f32();
-            prev = elapsed_time.as_secs_f32();
-            let rotation_angle = delta * std::f32::consts::FRAC_PI_2;
-            let rotation_quaternion = UnitQuaternion::from_euler_angles(
-                rotation_angle * roll_fact,
-                rotation_angle * pitch_fact,
-                rotation_angle * yaw_fact,
-            );
-
-            // Apply rotation to all cublets concerned (determined by f)
-            for i in 0..self.cublets.len() {
-                let cublet = self.cublets[i].borrow_mut();
-                if f_move(cublet.position) == n {
-                    cublet.node.append_rotation(&rotation_quaternion);
-                }
-            }
-
-            if elapsed_time >= animation_duration {
-                self.adjust_cublet_positions();
-                break;
+        let rotation_angle = fraction * std::f32::consts::FRAC_PI_2;
+        let rotation_quaternion = UnitQuaternion::from_euler_angles(
+            rotation_angle * roll_fact,
+            rotation_angle * pitch_fact,
+            rotation_angle * yaw_fact,
+        );
+
+        // Apply rotation to all cublets concerned (determined by f)
+        for i in 0..self.cublets.len() {
+            let cublet = self.cublets[i].borrow_mut();
+            if f_move(cublet.position) == stripe {
+                cublet.node.append_rotation(&rotation_quaternion);
             }
         }
+    }
+
+    /// This function update the positions of the cublets and the Cube at the end of a move
+    fn end_move(&mut self, mov: Move, n: usize) {
+        let s = self.cube.get_size() - 1;
+        let stripe = self.stripe(n);
+        let f_move = Self::stripe_coordinate(mov);
+        self.adjust_cublet_positions();
 
         // Update local coordinates
         for i in 0..self.cublets.len() {
             let cublet = self.cublets[i].borrow_mut();
-            if f_move(cublet.position) == n {
-                if mov == Move::H {
-                    f_h_coord(cublet);
-                } else if mov == Move::Hp {
-                    f_hp_coord(cublet);
-                } else if mov == Move::L {
-                    f_l_coord(cublet);
-                } else if mov == Move::Lp {
-                    f_lp_coord(cublet);
-                } else if mov == Move::V {
-                    f_v_coord(cublet);
-                } else {
-                    f_vp_coord(cublet);
+            if f_move(cublet.position) == stripe {
+                let (x, y, z) = cublet.position;
+                match mov {
+                    Move::H => cublet.set_position(z, y, s - x),
+                    Move::Hp => cublet.set_position(s - z, y, x),
+                    Move::L => cublet.set_position(s - y, x, z),
+                    Move::Lp => cublet.set_position(y, s - x, z),
+                    Move::V => cublet.set_position(x, s - z, y),
+                    Move::Vp => cublet.set_position(x, z, s - y),
                 }
             }
         }
+
+        // Keep the logical cube in sync
+        self.cube.apply_slice_move(mov, n);
+    }
+
+    /// This function change the color of a sticker, in the Cube and in the 3D view
+    ///
+    /// # Args
+    /// * 'face', 'row', 'col' - the sticker, as in Cube::set_sticker
+    /// * 'color' - the new color
+    pub fn set_sticker(&mut self, face: usize, row: usize, col: usize, color: Color) {
+        // The positions of the cublets must be up to date
+        self.finish_animations();
+        self.cube.set_sticker(face, row, col, color);
+        self.paint_sticker(face, row, col, color);
+    }
+
+    /// This function change the color of a sticker in the 3D view only
+    fn paint_sticker(&mut self, face: usize, row: usize, col: usize, color: Color) {
+        let (position, axis, side) = sticker_cublet(face, row, col, self.cube.get_size());
+        let (r, g, b) = self.sticker_rgb(color);
+        if let Some(cublet) = self.cublets.iter_mut().find(|cublet| cublet.position == position) {
+            cublet.face_toward(axis, side).set_color(r, g, b);
+        }
+    }
+
+    /// This function return the color drawn for a color of the Cube, with the theme
+    pub fn sticker_rgb(&self, color: Color) -> (f32, f32, f32) {
+        // The colors of the Cube are the faces of Cube::new_filled
+        ColorScheme::DELTA.rgb(&self.theme, color).map_or(EMPTY_COLOR, Rgb::to_unit)
+    }
+
+    /// This function return the theme of the cube
+    pub fn get_theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// This function return the logical cube displayed
+    pub fn get_cube(&self) -> &Cube {
+        &self.cube
     }
 
     /// This function will highlight the xth vertical stripe of the cube
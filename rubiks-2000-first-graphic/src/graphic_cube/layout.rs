@@ -0,0 +1,92 @@
+use super::gcube::SIZE;
+use crate::cube::cube::{ BACK, DOWN, FRONT, LEFT, RIGHT, UP };
+
+// The positions of the stickers of the Cube on the cublets of the Gcube.
+// The positions of the cublets grow to the left (x), the top (y) and the back (z), the front face is at z = 0.
+// A sticker is given by the position of its cublet and the normal of its face: an axis (0 for x, 1 for y,
+// 2 for z) and a side (1.0 or -1.0) along it.
+
+/// This function return the face of the Cube which has a given normal
+pub fn face_of_normal(axis: usize, side: f32) -> usize {
+    match (axis, side > 0.0) {
+        (0, true) => LEFT,
+        (0, false) => RIGHT,
+        (1, true) => UP,
+        (1, false) => DOWN,
+        (_, true) => BACK,
+        (_, false) => FRONT,
+    }
+}
+
+/// This function return the normal of a face of the Cube
+pub fn normal_of_face(face: usize) -> (usize, f32) {
+    match face {
+        LEFT => (0, 1.0),
+        RIGHT => (0, -1.0),
+        UP => (1, 1.0),
+        DOWN => (1, -1.0),
+        BACK => (2, 1.0),
+        _ => (2, -1.0),
+    }
+}
+
+/// This function return the sticker of the Cube on a face of a cublet
+///
+/// # Args
+/// * 'position' - the position of the cublet
+/// * 'axis', 'side' - the normal of the face of the cublet
+/// * 'size' - the size of the cube
+///
+/// # Return
+///
+/// The face, the row and the column of the sticker, as in Cube::get_sticker
+pub fn sticker_at(position: (usize, usize, usize), axis: usize, side: f32, size: usize) -> (usize, usize, usize) {
+    let s = size - 1;
+    let (x, y, z) = position;
+    let face = face_of_normal(axis, side);
+    // Each face is seen from the outside, with the orientation of the net of the Cube
+    let (row, col) = match face {
+        FRONT => (s - y, s - x),
+        BACK => (s - y, x),
+        LEFT => (s - y, s - z),
+        RIGHT => (s - y, z),
+        UP => (s - z, s - x),
+        _ => (z, s - x),
+    };
+    (face, row, col)
+}
+
+/// This function return the face of the cublet which carries a sticker of the Cube
+///
+/// # Return
+///
+/// The position of the cublet and the normal of its face (axis, side)
+pub fn sticker_cublet(face: usize, row: usize, col: usize, size: usize) -> ((usize, usize, usize), usize, f32) {
+    let s = size - 1;
+    let position = match face {
+        FRONT => (s - col, s - row, 0),
+        BACK => (col, s - row, s),
+        LEFT => (s, s - row, s - col),
+        RIGHT => (0, s - row, col),
+        UP => (s - col, s, s - row),
+        _ => (s - col, 0, row),
+    };
+    let (axis, side) = normal_of_face(face);
+    (position, axis, side)
+}
+
+/// This function return the center of a sticker, in the coordinates of the cube
+///
+/// # Args
+/// * 'face', 'row', 'col' - the sticker, as in Cube::get_sticker
+/// * 'size' - the size of the cube
+/// * 'gap' - the gap between two cublets
+/// * 'distance' - the distance of the sticker from the face of the cube (0.0 for the real sticker,
+///   more to draw it away from the cube)
+pub fn sticker_center(face: usize, row: usize, col: usize, size: usize, gap: f32, distance: f32) -> [f32; 3] {
+    let ((x, y, z), axis, side) = sticker_cublet(face, row, col, size);
+    let center = |index: usize| (index as f32 - (size - 1) as f32 / 2.0) * (SIZE + gap);
+    let mut point = [center(x), center(y), center(z)];
+    point[axis] = side * (center(size - 1) + SIZE / 2.0 + distance);
+    point
+}
@@ -1 +1,4 @@
-pub mod gcube;
\ No newline at end of file
+pub mod animation;
+pub mod gcube;
+pub mod layout;
+pub mod xray;
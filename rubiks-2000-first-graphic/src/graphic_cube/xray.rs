@@ -0,0 +1,99 @@
+use super::gcube::{Gcube, SIZE};
+use super::layout::{normal_of_face, sticker_center};
+use kiss3d::{
+    nalgebra::{Point3, Translation3, UnitQuaternion},
+    scene::SceneNode,
+    window::Window,
+};
+
+/// The distance between the faces of the cube and their mirrors
+const MIRROR_DISTANCE: f32 = 3.0;
+/// The size of a mirrored sticker, relative to the size of a cublet
+const MIRROR_STICKER: f32 = 0.9;
+
+/// The "x-ray" mode: the faces hidden from the camera are drawn away from the cube, on their side,
+/// as seen in a mirror placed behind them.
+pub struct XRay {
+    group: SceneNode,
+    /// The quads of the stickers, indexed by face then by row * size + col
+    stickers: Vec<Vec<SceneNode>>,
+    visible: bool,
+}
+
+impl XRay {
+    /// This function create the mirrors of the faces of a cube, hidden at first
+    ///
+    /// # Args
+    /// * 'window' - the window in which display the mirrors
+    /// * 'gcube' - the cube to mirror (for its size and its gap)
+    pub fn new(window: &mut Window, gcube: &Gcube) -> Self {
+        let mut group = window.add_group();
+        let size = gcube.get_cube().get_size();
+        let gap = gcube.get_theme().gap;
+
+        let mut stickers = Vec::new();
+        for face in 0..6 {
+            // The quads are created in the plane of the front and back faces
+            let rotation = match normal_of_face(face).0 {
+                0 => UnitQuaternion::from_euler_angles(0.0, std::f32::consts::FRAC_PI_2, 0.0),
+                1 => UnitQuaternion::from_euler_angles(std::f32::consts::FRAC_PI_2, 0.0, 0.0),
+                _ => UnitQuaternion::identity(),
+            };
+            let mut quads = Vec::new();
+            for row in 0..size {
+                for col in 0..size {
+                    let mut quad = group.add_quad(SIZE * MIRROR_STICKER, SIZE * MIRROR_STICKER, 1, 1);
+                    let [x, y, z] = sticker_center(face, row, col, size, gap, MIRROR_DISTANCE);
+                    quad.set_local_translation(Translation3::new(x, y, z));
+                    quad.set_local_rotation(rotation);
+                    // The mirrors are seen from the cube side
+                    quad.enable_backface_culling(false);
+                    quads.push(quad);
+                }
+            }
+            stickers.push(quads);
+        }
+        group.set_visible(false);
+
+        XRay { group, stickers, visible: false }
+    }
+
+    /// This function show or hide the mirrors
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+        self.group.set_visible(self.visible);
+    }
+
+    /// This function return true if the mirrors are shown
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// This function color the mirrors as the cube, and show only the faces hidden from the camera.
+    /// It must be called once per frame.
+    ///
+    /// # Args
+    /// * 'gcube' - the cube to mirror
+    /// * 'eye' - the position of the camera
+    pub fn update(&mut self, gcube: &Gcube, eye: Point3<f32>) {
+        if !self.visible {
+            return;
+        }
+        let cube = gcube.get_cube();
+        let size = cube.get_size();
+        let half = sticker_center(0, 0, 0, size, gcube.get_theme().gap, 0.0)[2].abs();
+
+        for (face, quads) in self.stickers.iter_mut().enumerate() {
+            // A face is hidden when the camera is behind its plane
+            let (axis, side) = normal_of_face(face);
+            let hidden = eye[axis] * side <= half;
+            for (i, quad) in quads.iter_mut().enumerate() {
+                quad.set_visible(hidden);
+                if hidden {
+                    let (r, g, b) = gcube.sticker_rgb(cube.get_sticker(face, i / size, i % size));
+                    quad.set_color(r, g, b);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// The number of moves of the algorithm shown around the current move
+pub const ALGORITHM_WINDOW: usize = 16;
+
+/// The turns per second (TPS) over the last seconds
+pub struct TurnRate {
+    window: Duration,
+    times: VecDeque<Duration>,
+    count: usize,
+}
+
+impl TurnRate {
+    /// This function create a counter of the turns
+    ///
+    /// # Args
+    /// * 'window' - the time over which the TPS is computed
+    pub fn new(window: Duration) -> Self {
+        TurnRate { window, times: VecDeque::new(), count: 0 }
+    }
+
+    /// This function count a turn
+    ///
+    /// # Args
+    /// * 'now' - the time of the turn, since the start of the viewer
+    pub fn record(&mut self, now: Duration) {
+        self.count += 1;
+        self.times.push_back(now);
+        self.forget(now);
+    }
+
+    /// This function return the number of turns since the start
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// This function return the turns per second over the window
+    pub fn tps(&mut self, now: Duration) -> f32 {
+        self.forget(now);
+        self.times.len() as f32 / self.window.as_secs_f32()
+    }
+
+    /// This function forget the turns older than the window
+    fn forget(&mut self, now: Duration) {
+        while self.times.front().is_some_and(|time| now.saturating_sub(*time) > self.window) {
+            self.times.pop_front();
+        }
+    }
+}
+
+/// This function return an algorithm with the current move between brackets.
+/// Only the moves around the current one are kept when the algorithm is too long.
+///
+/// # Args
+/// * 'moves' - the moves in notation
+/// * 'current' - the index of the current move, None before the first move
+pub fn highlight(moves: &[String], current: Option<usize>) -> String {
+    let center = current.unwrap_or(0);
+    let start = center.saturating_sub(ALGORITHM_WINDOW / 2).min(moves.len().saturating_sub(ALGORITHM_WINDOW));
+    let end = (start + ALGORITHM_WINDOW).min(moves.len());
+
+    let mut parts: Vec<String> = (start..end)
+        .map(|i| match Some(i) == current {
+            true => format!("[{}]", moves[i]),
+            false => moves[i].clone(),
+        })
+        .collect();
+    if start > 0 {
+        parts.insert(0, String::from("..."));
+    }
+    if end < moves.len() {
+        parts.push(String::from("..."));
+    }
+    parts.join(" ")
+}
+
+/// The points of view of the camera
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CameraPreset {
+    /// The front face only
+    Front,
+    /// The front, right and up faces
+    FrontRightUp,
+    /// The back face, the up face above it
+    Back,
+    /// The up face, the front face at the bottom
+    Top,
+}
+
+impl CameraPreset {
+    /// This function return the position of the camera, which looks at the center of the cube.
+    /// The cube has its front face at -z, its right face at -x and its up face at +y (see Gcube).
+    ///
+    /// # Args
+    /// * 'size' - the size of the cube, the camera goes back for the big cubes
+    pub fn eye(self, size: usize) -> (f32, f32, f32) {
+        let distance = 10.0 * (size as f32 / 3.0).max(1.0);
+        let (x, y, z): (f32, f32, f32) = match self {
+            CameraPreset::Front => (0.0, 0.0, -1.0),
+            CameraPreset::FrontRightUp => (-1.0, 1.0, -1.0),
+            CameraPreset::Back => (0.0, 0.3, 1.0),
+            // Not exactly above: the camera would not know where the top of the screen is
+            CameraPreset::Top => (0.0, 1.0, -0.01),
+        };
+        let norm = (x * x + y * y + z * z).sqrt();
+        (x * distance / norm, y * distance / norm, z * distance / norm)
+    }
+
+    /// This function return the name of the point of view
+    pub fn name(self) -> &'static str {
+        match self {
+            CameraPreset::Front => "front",
+            CameraPreset::FrontRightUp => "front-right-up",
+            CameraPreset::Back => "back",
+            CameraPreset::Top => "top",
+        }
+    }
+}
@@ -1,28 +1,360 @@
 #![allow(dead_code)] // This macros disable all the "unsed function/variable ..." warning, to simplify clarity during dev
 
+mod controls;
 mod cube;
+mod editor;
 mod graphic_cube;
+mod hud;
+mod player;
+
+// Test module
+#[cfg(test)]
+mod tests;
+
+use controls::controls::{Axis, History, Turn};
+use controls::picking::{pick, Drag, Ray};
 use cube::cube::Cube;
-use graphic_cube::gcube::{Gcube, Move::*};
+use editor::editor::{check_state, counts, face_letter, Editor, PALETTE};
+use graphic_cube::gcube::Gcube;
+use graphic_cube::layout::sticker_at;
+use graphic_cube::xray::XRay;
+use hud::hud::{CameraPreset, TurnRate};
+use player::player::{Player, Start};
+use rubiks_2000::cube::enums::Move as FaceMove;
+use rubiks_2000::cube::notation::parse_algorithm;
+use rubiks_2000::render::theme::Theme;
 
 extern crate kiss3d;
 extern crate nalgebra as na;
 
-use kiss3d::{ light::Light, window::Window};
+use kiss3d::{
+    camera::{ArcBall, Camera},
+    event::{Action, Key, Modifiers, MouseButton, WindowEvent},
+    light::Light,
+    nalgebra::{Point2, Point3, Vector2},
+    text::Font,
+    window::Window,
+};
+use rand::Rng;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "usage: cargo run -- [--theme <name or file>] [<facelets or scramble> <solution>]";
+const HELP: &str = "Space: play/pause   Right/Left: step   Backspace: restart   Up/Down: speed";
+const HELP_TURNS: &str = "R U F L D B M E S X Y Z: turn (Shift: inverse)   Ctrl+Z/Ctrl+Y: undo/redo   Drag a sticker: turn its slice";
+const HELP_PAINT: &str = "P: paint mode   1-6: color of the face U R F D L B   Click a sticker: paint it   Enter: print the state (no solve: no 3x3x3 solver yet)";
+const HELP_VIEW: &str = "F1: front   F2: front-right-up   F3: back   F4: top   F5: x-ray   H: hide the help";
+const TPS_WINDOW: f32 = 5.0;
+const MOVE_DURATION: f32 = 0.6;
+const SPEED_FACTOR: f32 = 1.5;
+
+/// This function read the theme, the starting state and the solution from the command line.
+/// Without a state, a random scramble is played backward.
+fn read_arguments() -> Result<(Cube, Vec<FaceMove>, Theme), String> {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let mut theme = Theme::DELTA;
+    if let Some(i) = args.iter().position(|arg| arg == "--theme") {
+        let name = args.get(i + 1).ok_or_else(|| String::from(USAGE))?;
+        theme = Theme::find(name).map_err(|error| error.to_string())?;
+        args.drain(i..i + 2);
+    }
+    let (cube, solution) = match args.as_slice() {
+        [] => {
+            let mut rng = rand::thread_rng();
+            let scramble: Vec<FaceMove> = (0..20).map(|_| FaceMove::from(rng.gen_range(0..18))).collect();
+            let solution: Vec<FaceMove> = scramble.iter().rev().map(|mv| mv.move_inv()).collect();
+            let cube = Start::Scramble(scramble).to_cube(&solution)?;
+            (cube, solution)
+        }
+        [start, solution] => {
+            let solution = parse_algorithm(solution)?;
+            let cube = Start::parse(start)?.to_cube(&solution)?;
+            (cube, solution)
+        }
+        _ => return Err(String::from(USAGE)),
+    };
+    Ok((cube, solution, theme))
+}
+
+/// This function return the turn of a key, with Shift for the inverse (None for the other keys)
+fn key_turn(key: Key, modifiers: Modifiers) -> Option<Turn> {
+    let prime = modifiers.contains(Modifiers::Shift);
+    let face = |mv: FaceMove| Some(Turn::Face(if prime { mv.move_inv() } else { mv }));
+    match key {
+        Key::R => face(FaceMove::R1),
+        Key::U => face(FaceMove::U1),
+        Key::F => face(FaceMove::F1),
+        Key::L => face(FaceMove::L1),
+        Key::D => face(FaceMove::D1),
+        Key::B => face(FaceMove::B1),
+        Key::M => Some(Turn::Slice(Axis::X, prime)),
+        Key::E => Some(Turn::Slice(Axis::Y, prime)),
+        Key::S => Some(Turn::Slice(Axis::Z, prime)),
+        Key::X => Some(Turn::Rotation(Axis::X, prime)),
+        Key::Y => Some(Turn::Rotation(Axis::Y, prime)),
+        Key::Z => Some(Turn::Rotation(Axis::Z, prime)),
+        _ => None,
+    }
+}
+
+/// This function queue the slice moves of a turn in the graphic cube
+fn play_turn(gcube: &mut Gcube, turn: Turn) {
+    for (mov, n) in turn.slices(gcube.get_cube().get_size()) {
+        gcube.apply_move(mov, n);
+    }
+}
+
+/// This function return the ray from the camera through a point of the window
+fn cursor_ray(window: &Window, camera: &ArcBall, x: f64, y: f64) -> Ray {
+    let size = window.size();
+    let (origin, direction) = camera.unproject(
+        &Point2::new(x as f32, y as f32),
+        &Vector2::new(size.x as f32, size.y as f32),
+    );
+    // The picking works with the nalgebra of the crate, which may not be the one of kiss3d
+    Ray {
+        origin: na::Point3::new(origin.x, origin.y, origin.z),
+        direction: na::Vector3::new(direction.x, direction.y, direction.z),
+    }
+}
+
+/// This function move the camera to a point of view
+fn set_view(camera: &mut ArcBall, preset: CameraPreset, size: usize) {
+    let (x, y, z) = preset.eye(size);
+    camera.look_at(Point3::new(x, y, z), Point3::origin());
+}
+
+/// This function draw lines of text (text, size, color) from the top left corner of the window
+///
+/// # Return
+///
+/// The height of the lines drawn
+fn draw_lines(window: &mut Window, font: &Rc<Font>, lines: &[(String, f32, Point3<f32>)]) -> f32 {
+    let mut y = 10.0;
+    for (text, size, color) in lines {
+        window.draw_text(text, &Point2::new(10.0, y), *size, font, color);
+        y += size * 1.1;
+    }
+    y
+}
+
+/// This function draw the palette of the paint mode on one line at the height 'y', each entry with the color of
+/// its stickers in the theme
+fn draw_palette(window: &mut Window, font: &Rc<Font>, editor: &Editor, gcube: &Gcube, y: f32) {
+    for (i, (text, color)) in editor.palette().iter().enumerate() {
+        let (r, g, b) = gcube.sticker_rgb(*color);
+        window.draw_text(text, &Point2::new(10.0 + 140.0 * i as f32, y), 40.0, font, &Point3::new(r, g, b));
+    }
+}
 
 fn main() {
+    let (cube, solution, theme) = match read_arguments() {
+        Ok(arguments) => arguments,
+        Err(error) => {
+            eprintln!("{}", error);
+            return;
+        }
+    };
+
     let mut window = Window::new("Rubik's 2000");
     window.set_light(Light::StickToCamera);
-    let cube = Cube::new_filled(3);
+    let (r, g, b) = theme.background.to_unit();
+    window.set_background_color(r, g, b);
+    let mut camera = ArcBall::new(Point3::new(0.0, 0.0, -10.0), Point3::origin());
+    let mut view = CameraPreset::FrontRightUp;
+    set_view(&mut camera, view, cube.get_size());
+
+    // The starting state is displayed directly, without playing the setup moves
+    let mut gcube = Gcube::with_theme(cube, theme, &mut window);
+    gcube.set_duration(Duration::from_secs_f32(MOVE_DURATION));
+    let mut xray = XRay::new(&mut window, &gcube);
+
+    let mut player = Player::new(solution);
+    let mut history = History::new();
+    let font = Font::default();
+    let mut last_frame = Instant::now();
+    let mut drag: Option<Drag> = None;
+    let mut editor: Option<Editor> = None;
+    let mut message = String::new();
+    let mut show_help = true;
+    let start = Instant::now();
+    let mut turn_rate = TurnRate::new(Duration::from_secs_f32(TPS_WINDOW));
+
+    // The only render loop: the animations advance by the time of each frame
+    while window.render_with_camera(&mut camera) {
+        let now = Instant::now();
+        let dt = now - last_frame;
+        last_frame = now;
+
+        for mut event in window.events().iter() {
+            // A click on the cube turns a slice (or paints a sticker), elsewhere it turns the camera
+            match event.value {
+                WindowEvent::MouseButton(MouseButton::Button1, Action::Press, _) if editor.is_some() => {
+                    if let Some((x, y)) = window.cursor_pos() {
+                        let size = gcube.get_cube().get_size();
+                        if let Some(hit) = pick(&cursor_ray(&window, &camera, x, y), size, gcube.get_theme().gap) {
+                            let (face, row, col) = sticker_at(hit.position, hit.axis, hit.side, size);
+                            gcube.set_sticker(face, row, col, editor.as_ref().unwrap().color());
+                            event.inhibited = true;
+                        }
+                    }
+                }
+                WindowEvent::MouseButton(MouseButton::Button1, Action::Press, _) => {
+                    if let Some((x, y)) = window.cursor_pos() {
+                        let size = gcube.get_cube().get_size();
+                        drag = Drag::start(&cursor_ray(&window, &camera, x, y), size, gcube.get_theme().gap);
+                        event.inhibited = drag.is_some();
+                    }
+                }
+                WindowEvent::CursorPos(x, y, _) => {
+                    if let Some(drag) = drag.as_mut() {
+                        if let Some((mov, n, fraction)) = drag.update(&cursor_ray(&window, &camera, x, y)) {
+                            gcube.drag_stripe(mov, n, fraction);
+                        }
+                        event.inhibited = true;
+                    }
+                }
+                WindowEvent::MouseButton(MouseButton::Button1, Action::Release, _) => {
+                    if drag.take().is_some() {
+                        let size = gcube.get_cube().get_size();
+                        for (mov, n) in gcube.release_stripe() {
+                            history.push(Turn::from_slice(mov, n, size));
+                            turn_rate.record(start.elapsed());
+                        }
+                        event.inhibited = true;
+                    }
+                }
+                _ => {}
+            }
+            if let WindowEvent::Key(key, Action::Press, modifiers) = event.value {
+                if modifiers.contains(Modifiers::Control) {
+                    let turn = match key {
+                        Key::Z => history.undo(),
+                        Key::Y => history.redo(),
+                        _ => None,
+                    };
+                    if let Some(turn) = turn {
+                        play_turn(&mut gcube, turn);
+                        turn_rate.record(start.elapsed());
+                    }
+                    continue;
+                }
+                if let Some(turn) = key_turn(key, modifiers) {
+                    history.push(turn);
+                    play_turn(&mut gcube, turn);
+                    turn_rate.record(start.elapsed());
+                    continue;
+                }
+                match key {
+                    Key::P => {
+                        editor = match editor {
+                            Some(_) => None,
+                            None => Some(Editor::new()),
+                        };
+                    }
+                    Key::Key1 | Key::Key2 | Key::Key3 | Key::Key4 | Key::Key5 | Key::Key6 => {
+                        let keys = [Key::Key1, Key::Key2, Key::Key3, Key::Key4, Key::Key5, Key::Key6];
+                        if let (Some(editor), Some(index)) = (editor.as_mut(), keys.iter().position(|k| *k == key)) {
+                            editor.select(index);
+                        }
+                    }
+                    // The solve is blocked by the missing 3x3x3 solver: the valid state is printed on the terminal,
+                    // to be copied for the replay of a solution
+                    Key::Return if editor.is_some() => {
+                        if let Ok(state) = check_state(gcube.get_cube()) {
+                            let facelets = state.to_string();
+                            message = format!("State printed on the terminal: {} (cargo run -- {} <solution>)", facelets, facelets);
+                            println!("{}", facelets);
+                            editor = None;
+                        }
+                    }
+                    Key::Space => player.toggle(),
+                    Key::Right => {
+                        if let Some(mv) = player.step_forward() {
+                            gcube.apply_face_move(mv);
+                            turn_rate.record(start.elapsed());
+                        }
+                    }
+                    Key::Left => {
+                        if let Some(mv) = player.step_back() {
+                            gcube.apply_face_move(mv);
+                            turn_rate.record(start.elapsed());
+                        }
+                    }
+                    Key::Back if drag.is_none() => {
+                        for mv in player.restart() {
+                            gcube.apply_face_move_instant(mv);
+                        }
+                    }
+                    Key::Up => gcube.set_speed(gcube.get_speed() * SPEED_FACTOR),
+                    Key::Down => gcube.set_speed(gcube.get_speed() / SPEED_FACTOR),
+                    Key::F1 | Key::F2 | Key::F3 | Key::F4 => {
+                        view = match key {
+                            Key::F1 => CameraPreset::Front,
+                            Key::F2 => CameraPreset::FrontRightUp,
+                            Key::F3 => CameraPreset::Back,
+                            _ => CameraPreset::Top,
+                        };
+                        set_view(&mut camera, view, gcube.get_cube().get_size());
+                    }
+                    Key::F5 => xray.toggle(),
+                    Key::H => show_help = !show_help,
+                    _ => {}
+                }
+            }
+        }
+        // When playing, the next move starts when the previous one is finished
+        if !gcube.is_animating() {
+            if let Some(mv) = player.next_auto() {
+                gcube.apply_face_move(mv);
+                turn_rate.record(start.elapsed());
+            }
+        }
+        gcube.update(dt);
+        xray.update(&gcube, camera.eye());
 
-    let mut gcube = Gcube::new(cube, &mut window);
+        // The overlay, large enough to be read on a projector
+        let white = Point3::new(1.0, 1.0, 1.0);
+        let yellow = Point3::new(1.0, 1.0, 0.0);
+        let view_name = match xray.is_visible() {
+            true => format!("{} + x-ray", view.name()),
+            false => String::from(view.name()),
+        };
+        let mut lines = vec![
+            (format!("{}   x{:.1}", player.status(), gcube.get_speed()), 50.0, white),
+            (player.highlighted_algorithm(), 50.0, yellow),
+            (
+                format!("Turns: {}   TPS: {:.1}   View: {}", turn_rate.count(), turn_rate.tps(start.elapsed()), view_name),
+                40.0,
+                white,
+            ),
+            (history.to_notation(), 40.0, white),
+        ];
+        if show_help {
+            for help in [HELP, HELP_TURNS, HELP_PAINT, HELP_VIEW] {
+                lines.push((String::from(help), 30.0, white));
+            }
+        }
+        lines.push((message.clone(), 30.0, white));
 
-    while window.render() {
-        gcube.apply_move(&mut window, H, 0);
-        gcube.apply_move(&mut window, Vp, 1);
-        gcube.apply_move(&mut window, L, 2);
-        gcube.apply_move(&mut window, Lp, 0);
-        gcube.apply_move(&mut window, V, 1);
-        gcube.apply_move(&mut window, Hp, 3);
+        // The painted state is checked at each frame
+        if let Some(editor) = &editor {
+            let counts = PALETTE
+                .iter()
+                .zip(counts(gcube.get_cube()))
+                .map(|(color, count)| format!("{} {}", face_letter(*color), count))
+                .collect::<Vec<_>>()
+                .join("  ");
+            let diagnostic = match check_state(gcube.get_cube()) {
+                Ok(_) => String::from("Valid state: Enter to print it (no 3x3x3 solver yet to solve it)"),
+                Err(error) => error.to_string(),
+            };
+            lines.push((counts, 30.0, white));
+            lines.push((diagnostic, 30.0, white));
+        }
+        let height = draw_lines(&mut window, &font, &lines);
+        if let Some(editor) = &editor {
+            draw_palette(&mut window, &font, editor, &gcube, height);
+        }
     }
-}
\ No newline at end of file
+}
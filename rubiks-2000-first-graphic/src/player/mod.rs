@@ -0,0 +1 @@
+pub mod player;
\ No newline at end of file
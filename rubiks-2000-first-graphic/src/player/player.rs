@@ -0,0 +1,169 @@
+use crate::cube::cube::Cube;
+use crate::hud::hud::highlight;
+use rubiks_2000::cube::enums::Move;
+use rubiks_2000::cube::face_cube::FaceCube;
+use rubiks_2000::cube::notation::{ algorithm_to_string, parse_algorithm };
+use rubiks_2000_core::convert::to_nxn;
+use rubiks_2000_core::moves::algorithm_to_slices;
+use rubiks_2000_core::scheme::ColorScheme;
+
+/// The state of the player of a solution: the moves already played and the play/pause state.
+/// The player only says which move to animate, the animation is done by the Gcube.
+pub struct Player {
+    moves: Vec<Move>,
+    position: usize,
+    playing: bool,
+}
+
+/// The starting state of a solution, read from the command line
+pub enum Start {
+    /// The state as a facelet string (see FaceCube)
+    Facelets(FaceCube),
+    /// The state reached from the solved cube by a scramble
+    Scramble(Vec<Move>),
+}
+
+impl Start {
+    /// This function parse a starting state: a facelet string of 54 letters, or a scramble in notation
+    pub fn parse(text: &str) -> Result<Self, String> {
+        if text.len() == 54 && !text.contains(' ') {
+            text.parse().map(Start::Facelets)
+        } else {
+            parse_algorithm(text).map(Start::Scramble)
+        }
+    }
+
+    /// This function return the moves which bring the solved cube to this state.
+    /// For a facelet state, the solution must solve it: the state is reached with the inverse of the solution.
+    ///
+    /// # Args
+    /// * 'solution' - the solution played from this state
+    pub fn setup_moves(&self, solution: &[Move]) -> Result<Vec<Move>, String> {
+        match self {
+            Start::Scramble(moves) => Ok(moves.clone()),
+            Start::Facelets(state) => {
+                let mut cube = *state;
+                for mv in solution {
+                    cube.apply_move(*mv);
+                }
+                if cube != FaceCube::new() {
+                    return Err(String::from("the solution does not solve the starting state"));
+                }
+                Ok(solution.iter().rev().map(|mv| mv.move_inv()).collect())
+            }
+        }
+    }
+
+    /// This function return the starting state as a 3x3x3 cube, to display it without playing the setup moves.
+    /// For a facelet state, the solution must solve it (see setup_moves).
+    ///
+    /// # Args
+    /// * 'solution' - the solution played from this state
+    pub fn to_cube(&self, solution: &[Move]) -> Result<Cube, String> {
+        match self {
+            Start::Scramble(moves) => {
+                let mut cube = Cube::new_filled(3);
+                for (mov, n) in algorithm_to_slices(moves, 3) {
+                    cube.apply_slice_move(mov, n);
+                }
+                Ok(cube)
+            }
+            Start::Facelets(state) => {
+                self.setup_moves(solution)?;
+                Ok(to_nxn(state, &ColorScheme::DELTA))
+            }
+        }
+    }
+}
+
+impl Player {
+    /// This function create a player, paused before the first move
+    ///
+    /// # Args
+    /// * 'moves' - the moves of the solution
+    pub fn new(moves: Vec<Move>) -> Self {
+        Player { moves, position: 0, playing: false }
+    }
+
+    /// This function return the number of moves already played
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// This function return the number of moves of the solution
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// This function return true if the solution has no move
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// This function return true if the solution is played automatically
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// This function switch between play and pause
+    pub fn toggle(&mut self) {
+        self.playing = !self.playing;
+    }
+
+    /// This function return the next move to animate when playing (None when paused or finished)
+    pub fn next_auto(&mut self) -> Option<Move> {
+        if !self.playing {
+            return None;
+        }
+        let mv = self.step_forward();
+        if mv.is_none() {
+            self.playing = false;
+        }
+        mv
+    }
+
+    /// This function return the next move of the solution to animate, None at the end
+    pub fn step_forward(&mut self) -> Option<Move> {
+        let mv = *self.moves.get(self.position)?;
+        self.position += 1;
+        Some(mv)
+    }
+
+    /// This function return the move which undoes the last move played, None at the start
+    pub fn step_back(&mut self) -> Option<Move> {
+        if self.position == 0 {
+            return None;
+        }
+        self.position -= 1;
+        Some(self.moves[self.position].move_inv())
+    }
+
+    /// This function go back to the start, paused, and return the moves which undo the moves played
+    pub fn restart(&mut self) -> Vec<Move> {
+        self.playing = false;
+        let undo = self.moves[..self.position].iter().rev().map(|mv| mv.move_inv()).collect();
+        self.position = 0;
+        undo
+    }
+
+    /// This function return the text displayed on the screen: the last move played and the move counter
+    pub fn status(&self) -> String {
+        let current = match self.position {
+            0 => String::from("-"),
+            position => self.moves[position - 1].to_notation(),
+        };
+        let state = if self.playing { "playing" } else { "paused" };
+        format!("Move {}/{}: {} ({})", self.position, self.moves.len(), current, state)
+    }
+
+    /// This function return the whole solution in notation
+    pub fn algorithm(&self) -> String {
+        algorithm_to_string(&self.moves)
+    }
+
+    /// This function return the solution in notation with the last move played between brackets
+    pub fn highlighted_algorithm(&self) -> String {
+        let moves: Vec<String> = self.moves.iter().map(|mv| mv.to_notation()).collect();
+        highlight(&moves, self.position.checked_sub(1))
+    }
+}
@@ -0,0 +1,513 @@
+#[cfg(test)]
+mod tests {
+    use crate::controls::controls::{Axis, History, Turn};
+    use crate::controls::picking::{pick, snap, Drag, Ray, DRAG_QUARTER};
+    use crate::cube::cube::{Color, Cube};
+    use crate::editor::editor::{check_state, face_letter, EditError, Editor, PALETTE};
+    use crate::graphic_cube::animation::{AnimationQueue, Easing, Step};
+    use crate::graphic_cube::gcube::Move;
+    use crate::graphic_cube::layout::{face_of_normal, normal_of_face, sticker_at, sticker_center, sticker_cublet};
+    use crate::hud::hud::{highlight, TurnRate, ALGORITHM_WINDOW};
+    use crate::player::player::{Player, Start};
+    use na::{Point3, Vector3};
+    use rand::Rng;
+    use rubiks_2000::cube::enums::Move as FaceMove;
+    use rubiks_2000::cube::face_cube::FaceCube;
+    use rubiks_2000::cube::notation::parse_algorithm;
+    use rubiks_2000::cube::enums::Color as Face;
+    use rubiks_2000_core::moves::algorithm_to_slices;
+    use rubiks_2000_core::notation::BigMove;
+    use rubiks_2000_core::scheme::ColorScheme;
+    use std::time::Duration;
+
+    /// The sum of the fractions of the steps of each move, in the order of the moves
+    fn total_fractions(steps: &[Step]) -> Vec<(Move, usize, f32)> {
+        let mut totals: Vec<(Move, usize, f32)> = Vec::new();
+        for step in steps {
+            match totals.last_mut() {
+                Some((mov, n, total)) if (*mov, *n) == (step.mov, step.n) => *total += step.fraction,
+                _ => totals.push((step.mov, step.n, step.fraction)),
+            }
+        }
+        totals
+    }
+
+    #[test]
+    /// The easing curves start at 0, end at 1, and are clamped outside of the move
+    fn test_easing() {
+        for (easing, middle) in [(Easing::Linear, 0.5), (Easing::EaseInOut, 0.5), (Easing::EaseOut, 0.75)] {
+            assert_eq!(easing.apply(0.0), 0.0, "Failed easing - {:?}", easing);
+            assert_eq!(easing.apply(0.5), middle, "Failed easing - {:?}", easing);
+            assert_eq!(easing.apply(1.0), 1.0, "Failed easing - {:?}", easing);
+            assert_eq!(easing.apply(-1.0), 0.0, "Failed easing - {:?}", easing);
+            assert_eq!(easing.apply(2.0), 1.0, "Failed easing - {:?}", easing);
+        }
+    }
+
+    #[test]
+    /// The moves are animated one after the other, and each one is turned by exactly a quarter turn
+    fn test_animation_update() {
+        let mut queue = AnimationQueue::new();
+        queue.easing = Easing::Linear;
+        queue.duration = Duration::from_millis(500);
+        assert!(queue.update(Duration::from_millis(100)).is_empty());
+
+        queue.push(Move::V, 0);
+        queue.push(Move::H, 1);
+        assert_eq!(queue.len(), 2);
+
+        // Half of the first move
+        let steps = queue.update(Duration::from_millis(250));
+        assert_eq!(steps, vec![Step { mov: Move::V, n: 0, fraction: 0.5, finished: false }]);
+
+        // The first move ends, the second one starts in the same frame
+        let steps = queue.update(Duration::from_millis(250));
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0], Step { mov: Move::V, n: 0, fraction: 0.5, finished: true });
+        assert_eq!((steps[1].mov, steps[1].n, steps[1].finished), (Move::H, 1, false));
+        assert_eq!(queue.len(), 1);
+
+        // A long frame ends the last move, the time left is lost
+        let steps = queue.update(Duration::from_millis(2000));
+        assert_eq!(steps.len(), 1);
+        assert!(steps[0].finished);
+        assert_eq!(steps[0].fraction, 1.0);
+        assert!(queue.is_empty());
+        assert!(queue.update(Duration::from_millis(100)).is_empty());
+
+        // Several moves can end in one frame, and each one turns by a quarter turn with any easing
+        queue.easing = Easing::EaseInOut;
+        for n in 0..3 {
+            queue.push(Move::L, n);
+        }
+        let mut steps = queue.update(Duration::from_millis(300));
+        steps.extend(queue.update(Duration::from_millis(1200)));
+        assert!(queue.is_empty());
+        let totals = total_fractions(&steps);
+        assert_eq!(totals.len(), 3);
+        for (n, (mov, slice, total)) in totals.into_iter().enumerate() {
+            assert_eq!((mov, slice), (Move::L, n));
+            assert!((total - 1.0).abs() < 1e-6, "Failed move - {}: {}", n, total);
+        }
+    }
+
+    #[test]
+    /// The speed changes the time of the animations, even in the middle of a move
+    fn test_animation_speed() {
+        let mut queue = AnimationQueue::new();
+        queue.easing = Easing::Linear;
+        queue.duration = Duration::from_millis(500);
+        queue.set_speed(2.0);
+        assert_eq!(queue.get_speed(), 2.0);
+
+        queue.push(Move::V, 0);
+        let steps = queue.update(Duration::from_millis(125));
+        assert_eq!(steps[0].fraction, 0.5);
+
+        queue.set_speed(0.5);
+        let steps = queue.update(Duration::from_millis(250));
+        assert_eq!(steps[0].fraction, 0.25);
+        assert!(!steps[0].finished);
+
+        // A null speed would never end the moves
+        queue.set_speed(0.0);
+        assert!(queue.get_speed() > 0.0);
+    }
+
+    #[test]
+    /// finish_all ends the current move from where it is, then the moves waiting
+    fn test_animation_finish_all() {
+        let mut queue = AnimationQueue::new();
+        queue.easing = Easing::Linear;
+        queue.duration = Duration::from_millis(500);
+        assert!(queue.finish_all().is_empty());
+
+        queue.push(Move::V, 0);
+        queue.push(Move::Hp, 2);
+        queue.push(Move::Lp, 1);
+        queue.update(Duration::from_millis(250));
+
+        let steps = queue.finish_all();
+        assert_eq!(
+            steps,
+            vec![
+                Step { mov: Move::V, n: 0, fraction: 0.5, finished: true },
+                Step { mov: Move::Hp, n: 2, fraction: 1.0, finished: true },
+                Step { mov: Move::Lp, n: 1, fraction: 1.0, finished: true },
+            ]
+        );
+        assert!(queue.is_empty());
+        assert!(!queue.is_busy());
+        assert!(queue.update(Duration::from_millis(100)).is_empty());
+    }
+
+    #[test]
+    /// The normal of each face gives back the face, and the normal of the face of each normal is this normal
+    fn test_face_normals() {
+        for face in 0..6 {
+            let (axis, side) = normal_of_face(face);
+            assert_eq!(face_of_normal(axis, side), face, "Failed face - {}", face);
+        }
+        for axis in 0..3 {
+            for side in [1.0, -1.0] {
+                assert_eq!(normal_of_face(face_of_normal(axis, side)), (axis, side), "Failed normal - {} {}", axis, side);
+            }
+        }
+    }
+
+    #[test]
+    /// Each sticker is on the outer face of a cublet, and this face of the cublet gives back the sticker
+    fn test_sticker_layout() {
+        for size in 2..=5 {
+            let mut cublet_faces = Vec::new();
+            for face in 0..6 {
+                for row in 0..size {
+                    for col in 0..size {
+                        let (position, axis, side) = sticker_cublet(face, row, col, size);
+                        let coordinates = [position.0, position.1, position.2];
+                        assert!(coordinates.iter().all(|c| *c < size), "Failed sticker - {} {} {} {}", size, face, row, col);
+                        assert_eq!(coordinates[axis], if side > 0.0 { size - 1 } else { 0 });
+                        assert_eq!(sticker_at(position, axis, side, size), (face, row, col), "Failed size - {}", size);
+                        cublet_faces.push((position, axis, side > 0.0));
+                    }
+                }
+            }
+            // No two stickers on the same face of a cublet
+            let count = cublet_faces.len();
+            cublet_faces.sort();
+            cublet_faces.dedup();
+            assert_eq!(cublet_faces.len(), count, "Failed size - {}", size);
+        }
+    }
+
+    /// This function play the slice moves of a turn and of a move of the big cube notation on the same scrambled
+    /// cube, and return the two cubes
+    fn play_both(turn: Turn, notation: &str, size: usize) -> (Cube, Cube) {
+        let mut cube = Cube::new_filled(size);
+        cube.shuffle(30);
+        let mut expected = cube.clone();
+        for (mov, n) in turn.slices(size) {
+            cube.apply_slice_move(mov, n);
+        }
+        for (mov, n) in notation.parse::<BigMove>().unwrap().to_slices(size).unwrap() {
+            expected.apply_slice_move(mov, n);
+        }
+        (cube, expected)
+    }
+
+    #[test]
+    /// The slices and the rotations of the keyboard turn the layers of their notation, in the same direction
+    fn test_turn_slices() {
+        let turns = [
+            (Turn::Slice(Axis::X, false), "M"),
+            (Turn::Slice(Axis::Y, false), "E"),
+            (Turn::Slice(Axis::Z, false), "S"),
+            (Turn::Rotation(Axis::X, false), "x"),
+            (Turn::Rotation(Axis::Y, false), "y"),
+            (Turn::Rotation(Axis::Z, false), "z"),
+            (Turn::Face(FaceMove::R1), "R"),
+        ];
+        for (turn, notation) in turns {
+            assert_eq!(turn.to_notation(), notation);
+            for size in 3..=5 {
+                let (cube, expected) = play_both(turn, notation, size);
+                assert_eq!(cube, expected, "Failed turn - {} on {}", notation, size);
+                let (cube, expected) = play_both(turn.inverse(), &format!("{}'", notation), size);
+                assert_eq!(cube, expected, "Failed turn - {}' on {}", notation, size);
+            }
+        }
+
+        // The inner slices of the bigger cubes are all the layers but the faces
+        assert_eq!(Turn::Slice(Axis::X, false).slices(5).len(), 3);
+        assert_eq!(Turn::Rotation(Axis::Y, true).slices(4).len(), 4);
+    }
+
+    #[test]
+    /// Undo returns the inverse of the last turn, redo plays it again, and a new turn clears the turns to redo
+    fn test_history() {
+        let mut history = History::new();
+        assert_eq!(history.undo(), None);
+        assert_eq!(history.redo(), None);
+
+        history.push(Turn::Face(FaceMove::R1));
+        history.push(Turn::Slice(Axis::X, false));
+        history.push(Turn::Rotation(Axis::Y, true));
+        assert_eq!(history.to_notation(), "R M y'");
+
+        assert_eq!(history.undo(), Some(Turn::Rotation(Axis::Y, false)));
+        assert_eq!(history.undo(), Some(Turn::Slice(Axis::X, true)));
+        assert_eq!(history.to_notation(), "R");
+        assert_eq!(history.redo(), Some(Turn::Slice(Axis::X, false)));
+        assert_eq!(history.to_notation(), "R M");
+
+        // y' can not be redone after a new turn
+        history.push(Turn::Face(FaceMove::U3));
+        assert_eq!(history.redo(), None);
+        assert_eq!(history.to_notation(), "R M U'");
+
+        assert_eq!(history.undo(), Some(Turn::Face(FaceMove::U1)));
+        assert_eq!(history.undo(), Some(Turn::Slice(Axis::X, true)));
+        assert_eq!(history.undo(), Some(Turn::Face(FaceMove::R3)));
+        assert_eq!(history.undo(), None);
+        assert_eq!(history.to_notation(), "");
+    }
+
+    /// The gap between the cublets in the tests of the picking
+    const GAP: f32 = 0.05;
+
+    /// This function return the ray from a camera in front of a point of a face, toward the face
+    fn ray_toward(point: Point3<f32>, axis: usize, side: f32) -> Ray {
+        let mut direction = Vector3::zeros();
+        direction[axis] = -side;
+        Ray { origin: point - direction * 10.0, direction }
+    }
+
+    /// This function return the center of a sticker as a point
+    fn sticker_point(face: usize, row: usize, col: usize, size: usize) -> Point3<f32> {
+        let [x, y, z] = sticker_center(face, row, col, size, GAP, 0.0);
+        Point3::new(x, y, z)
+    }
+
+    #[test]
+    /// A ray toward a sticker hits this sticker, a ray beside the cube hits nothing
+    fn test_pick() {
+        for size in 2..=4 {
+            for face in 0..6 {
+                let (axis, side) = normal_of_face(face);
+                for row in 0..size {
+                    for col in 0..size {
+                        let hit = pick(&ray_toward(sticker_point(face, row, col, size), axis, side), size, GAP).unwrap();
+                        let expected = sticker_cublet(face, row, col, size);
+                        assert_eq!((hit.position, hit.axis, hit.side), expected, "Failed sticker - {} {} {} {}", size, face, row, col);
+                    }
+                }
+            }
+        }
+
+        // The ray through the center of the front face, from the camera of the front view
+        let hit = pick(&Ray { origin: Point3::new(0.0, 0.0, -10.0), direction: Vector3::new(0.0, 0.0, 1.0) }, 3, GAP).unwrap();
+        assert_eq!((hit.position, hit.axis, hit.side), ((1, 1, 0), 2, -1.0));
+
+        // Beside the cube, and away from the cube
+        assert_eq!(pick(&Ray { origin: Point3::new(0.0, 3.0, -10.0), direction: Vector3::new(0.0, 0.0, 1.0) }, 3, GAP), None);
+        assert_eq!(pick(&Ray { origin: Point3::new(0.0, 0.0, -10.0), direction: Vector3::new(0.0, 0.0, -1.0) }, 3, GAP), None);
+    }
+
+    #[test]
+    /// A drag of a quarter turn along a face brings the sticker dragged on the face toward which it is dragged
+    fn test_drag() {
+        let size = 3;
+        for face in 0..6 {
+            let (axis, side) = normal_of_face(face);
+            for drag_axis in (0..3).filter(|other| *other != axis) {
+                for drag_side in [1.0, -1.0] {
+                    for (row, col) in [(0, 0), (0, 1), (1, 1), (2, 1)] {
+                        let start = sticker_point(face, row, col, size);
+                        let mut drag = Drag::start(&ray_toward(start, axis, side), size, GAP).unwrap();
+                        let mut end = start;
+                        end[drag_axis] += drag_side * DRAG_QUARTER;
+                        let (mov, n, fraction) = drag.update(&ray_toward(end, axis, side)).unwrap();
+                        assert!((fraction.abs() - 1.0).abs() < 1e-4);
+
+                        // The sticker dragged is the only empty one
+                        let mut cube = Cube::new_filled(size);
+                        cube.set_sticker(face, row, col, Color::Empty);
+                        let played = if snap(fraction) < 0 { mov.inverse() } else { mov };
+                        cube.apply_slice_move(played, n);
+                        let moved = (0..6)
+                            .find(|other| (0..size).any(|r| (0..size).any(|c| cube.get_sticker(*other, r, c) == Color::Empty)))
+                            .unwrap();
+                        assert_eq!(
+                            normal_of_face(moved),
+                            (drag_axis, drag_side),
+                            "Failed drag - face {} sticker {} {} along {} {}",
+                            face, row, col, drag_axis, drag_side
+                        );
+                    }
+                }
+            }
+        }
+
+        // The top row of the front face dragged to the right of the screen (toward -x) is U'
+        let start = sticker_point(0, 0, 1, size);
+        let mut drag = Drag::start(&ray_toward(start, 2, -1.0), size, GAP).unwrap();
+        assert_eq!(drag.update(&ray_toward(start, 2, -1.0)), None);
+        let (mov, n, fraction) = drag.update(&ray_toward(start - Vector3::new(1.0, 0.0, 0.0), 2, -1.0)).unwrap();
+        assert_eq!((mov, n), (Move::Hp, 0));
+        assert!(fraction < 0.0);
+        // The direction is kept once chosen
+        let (mov, _, fraction) = drag.update(&ray_toward(start + Vector3::new(0.0, -2.0, 0.0), 2, -1.0)).unwrap();
+        assert_eq!((mov, fraction), (Move::Hp, 0.0));
+    }
+
+    #[test]
+    /// A drag snaps to the nearest quarter turn, the half turns away from zero
+    fn test_snap() {
+        assert_eq!(snap(0.0), 0);
+        assert_eq!(snap(0.49), 0);
+        assert_eq!(snap(0.51), 1);
+        assert_eq!(snap(0.5), 1);
+        assert_eq!(snap(-0.49), 0);
+        assert_eq!(snap(-0.51), -1);
+        assert_eq!(snap(-0.5), -1);
+        assert_eq!(snap(1.49), 1);
+        assert_eq!(snap(1.51), 2);
+        assert_eq!(snap(-1.49), -1);
+        assert_eq!(snap(-1.51), -2);
+    }
+
+    #[test]
+    /// The position of the player stays between the start and the end of the solution
+    fn test_player_steps() {
+        let mut player = Player::new(parse_algorithm("R U F'").unwrap());
+        assert_eq!((player.position(), player.len()), (0, 3));
+        assert_eq!(player.highlighted_algorithm(), "R U F'");
+
+        // Nothing to undo at the start
+        assert_eq!(player.step_back(), None);
+        assert_eq!(player.position(), 0);
+
+        assert_eq!(player.step_forward(), Some(FaceMove::R1));
+        assert_eq!(player.step_forward(), Some(FaceMove::U1));
+        assert_eq!(player.highlighted_algorithm(), "R [U] F'");
+        assert_eq!(player.step_back(), Some(FaceMove::U3));
+        assert_eq!(player.position(), 1);
+        assert_eq!(player.highlighted_algorithm(), "[R] U F'");
+
+        // Nothing to play at the end
+        assert_eq!(player.step_forward(), Some(FaceMove::U1));
+        assert_eq!(player.step_forward(), Some(FaceMove::F3));
+        assert_eq!(player.step_forward(), None);
+        assert_eq!(player.position(), 3);
+        assert_eq!(player.highlighted_algorithm(), "R U [F']");
+
+        // The restart undoes the moves played, the last one first
+        assert_eq!(player.restart(), vec![FaceMove::F1, FaceMove::U3, FaceMove::R3]);
+        assert_eq!(player.position(), 0);
+        assert!(player.restart().is_empty());
+    }
+
+    #[test]
+    /// The automatic play gives the moves only when playing, and pauses at the end
+    fn test_player_auto() {
+        let mut player = Player::new(parse_algorithm("R U").unwrap());
+        assert_eq!(player.next_auto(), None);
+        assert_eq!(player.position(), 0);
+
+        player.toggle();
+        assert!(player.is_playing());
+        assert_eq!(player.next_auto(), Some(FaceMove::R1));
+        assert_eq!(player.next_auto(), Some(FaceMove::U1));
+        assert_eq!(player.next_auto(), None);
+        assert!(!player.is_playing());
+        assert_eq!(player.position(), 2);
+
+        // The restart pauses the player
+        player.step_back();
+        player.toggle();
+        player.restart();
+        assert!(!player.is_playing());
+        assert_eq!(player.next_auto(), None);
+
+        let mut empty = Player::new(Vec::new());
+        empty.toggle();
+        assert_eq!(empty.next_auto(), None);
+        assert_eq!(empty.highlighted_algorithm(), "");
+    }
+
+    #[test]
+    /// A facelet state is set up with the inverse of its solution, which gives back the state
+    fn test_start_facelets() {
+        let mut rng = rand::thread_rng();
+        let scramble: Vec<FaceMove> = (0..20).map(|_| FaceMove::from(rng.gen_range(0..18))).collect();
+        let mut state = FaceCube::new();
+        for mv in &scramble {
+            state.apply_move(*mv);
+        }
+        let solution: Vec<FaceMove> = scramble.iter().rev().map(|mv| mv.move_inv()).collect();
+
+        let mut expected = Cube::new_filled(3);
+        for (mov, n) in algorithm_to_slices(&scramble, 3) {
+            expected.apply_slice_move(mov, n);
+        }
+        let start = Start::Facelets(state);
+        assert_eq!(start.setup_moves(&solution), Ok(scramble.clone()));
+        assert_eq!(start.to_cube(&solution), Ok(expected.clone()));
+        assert_eq!(Start::Scramble(scramble.clone()).to_cube(&[]), Ok(expected));
+
+        // The solution must solve the state
+        assert!(start.setup_moves(&solution[1..]).is_err());
+        assert!(start.to_cube(&solution[1..]).is_err());
+    }
+
+    #[test]
+    /// The palette is named by the faces whose color the theme draws, as the stickers of the Gcube
+    fn test_palette() {
+        for (index, letter) in ['U', 'R', 'F', 'D', 'L', 'B'].into_iter().enumerate() {
+            assert_eq!(PALETTE[index], ColorScheme::DELTA.color(Face::from(index)));
+            assert_eq!(face_letter(PALETTE[index]), letter);
+        }
+        assert_eq!(face_letter(Color::Empty), '?');
+
+        let mut editor = Editor::new();
+        editor.select(8);
+        assert_eq!(editor.color(), ColorScheme::DELTA.color(Face::F));
+        let palette = editor.palette();
+        assert_eq!(palette[2], (String::from("[3 F]"), Color::White));
+        assert_eq!(palette[3], (String::from("4 D"), Color::Green));
+
+        // The errors name the faces too
+        let mut cube = Cube::new_filled(3);
+        cube.set_sticker(0, 0, 0, Color::Blue);
+        assert_eq!(check_state(&cube), Err(EditError::Count(Color::Blue, 10)));
+        assert_eq!(EditError::Count(Color::Blue, 10).to_string(), "The color of the face U is on 10 stickers instead of 9");
+    }
+
+    #[test]
+    /// A long algorithm is cut around the current move, with "..." where moves are hidden
+    fn test_highlight() {
+        let moves: Vec<String> = (0..20).map(|i| format!("M{}", i)).collect();
+        let shown = |start: usize, end: usize, current: usize| {
+            (start..end).map(|i| if i == current { format!("[M{}]", i) } else { format!("M{}", i) }).collect::<Vec<_>>()
+        };
+
+        // At the start
+        let mut expected = shown(0, ALGORITHM_WINDOW, usize::MAX);
+        expected.push(String::from("..."));
+        assert_eq!(highlight(&moves, None), expected.join(" "));
+        expected[0] = String::from("[M0]");
+        assert_eq!(highlight(&moves, Some(0)), expected.join(" "));
+
+        // In the middle, the current move is in the middle of the window
+        let mut expected = vec![String::from("...")];
+        expected.extend(shown(2, 2 + ALGORITHM_WINDOW, 10));
+        expected.push(String::from("..."));
+        assert_eq!(highlight(&moves, Some(10)), expected.join(" "));
+
+        // At the end, the window ends with the algorithm
+        let mut expected = vec![String::from("...")];
+        expected.extend(shown(20 - ALGORITHM_WINDOW, 20, 19));
+        assert_eq!(highlight(&moves, Some(19)), expected.join(" "));
+
+        // A short algorithm is not cut
+        assert_eq!(highlight(&moves[..3], Some(2)), "M0 M1 [M2]");
+        assert_eq!(highlight(&[], None), "");
+    }
+
+    #[test]
+    /// The turns older than the window do not count in the TPS, but still count in the number of turns
+    fn test_turn_rate() {
+        let mut rate = TurnRate::new(Duration::from_secs(5));
+        assert_eq!(rate.tps(Duration::ZERO), 0.0);
+        for time in [0, 1, 2, 6] {
+            rate.record(Duration::from_secs(time));
+        }
+        assert_eq!(rate.count(), 4);
+
+        // The turn at 0s is 6s old, the turn at 1s is exactly at the limit of the window
+        assert_eq!(rate.tps(Duration::from_secs(6)), 3.0 / 5.0);
+        assert_eq!(rate.tps(Duration::from_secs(8)), 1.0 / 5.0);
+        assert_eq!(rate.tps(Duration::from_secs(20)), 0.0);
+        assert_eq!(rate.count(), 4);
+    }
+}
@@ -0,0 +1,184 @@
+// Correction of noisy color readings: given the probability of each color for each sticker (from the camera),
+// find the most likely legal cube.
+//
+// The cost of a cube is the sum over the stickers of -ln(probability of its color). The legal cubes are
+// enumerated by placing the cubies one by one, as in the partial cube resolver, the cheapest (cubie, orientation)
+// first at each position. This is a branch and bound search: a branch is cut as soon as its cost plus the
+// cheapest cost of the positions left reaches the cost of the best legal cube found so far. Nothing else is cut,
+// so the result is the most likely legal cube (when the reading is bad, the search can take longer).
+
+use super::cube::Cube;
+use super::defs::*;
+use super::enums::{ Color, Corner, Edge, Facelet };
+use super::face_cube::FaceCube;
+
+/// Used instead of a null probability, which would have an infinite cost
+const MIN_PROBABILITY: f32 = 1e-9;
+
+/// The probabilities of the six colors (indexed by the Color enum) for each sticker (indexed by the Facelet enum)
+pub type ColorProbabilities = [[f32; N_COLORS]; 54];
+
+/// The most likely legal cube found from a noisy reading
+#[derive(Debug, Clone, PartialEq)]
+pub struct Correction {
+    /// The legal cube
+    pub cube: FaceCube,
+    /// The stickers whose color is not the most likely one, in the order of the Facelet enum
+    pub changed: Vec<usize>,
+    /// The cost of the cube: the sum over the stickers of -ln(probability of its color)
+    pub cost: f32,
+}
+
+/// The cost of the color `color` for the sticker `index`
+fn color_cost(probabilities: &ColorProbabilities, index: usize, color: Color) -> f32 {
+    -probabilities[index][color as usize].max(MIN_PROBABILITY).ln()
+}
+
+/// The cost of a cube (see Correction::cost)
+fn cube_cost(probabilities: &ColorProbabilities, cube: &FaceCube) -> f32 {
+    (0..54).map(|index| color_cost(probabilities, index, cube.get_facelet(index))).sum()
+}
+
+/// The most likely color of the sticker `index`
+fn best_color(probabilities: &ColorProbabilities, index: usize) -> Color {
+    let best = (0..N_COLORS)
+        .max_by(|&a, &b| probabilities[index][a].total_cmp(&probabilities[index][b]))
+        .unwrap();
+    Color::from(best)
+}
+
+/// The possible (cost, cubie, orientation) at each position, the cheapest first: the cost of a cubie is the cost
+/// of its colors on the stickers of the position (same convention as Cube::to_facelet_cube)
+fn candidates<const N: usize>(
+    probabilities: &ColorProbabilities,
+    facelets: &[[Facelet; N]],
+    colors: &[[Color; N]]
+) -> Vec<Vec<(f32, usize, u8)>> {
+    facelets
+        .iter()
+        .map(|position| {
+            let mut candidates: Vec<(f32, usize, u8)> = (0..colors.len())
+                .flat_map(|j| (0..N).map(move |o| (j, o)))
+                .map(|(j, o)| {
+                    let cost = (0..N).map(|k| color_cost(probabilities, position[(k + o) % N] as usize, colors[j][k])).sum();
+                    (cost, j, o as u8)
+                })
+                .collect();
+            candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+            candidates
+        })
+        .collect()
+}
+
+/// The cheapest cost of the positions from each position to the end, for each position and the end
+fn bounds(candidates: &[Vec<(f32, usize, u8)>], end: f32) -> Vec<f32> {
+    let mut bounds = vec![end; candidates.len() + 1];
+    for pos in (0..candidates.len()).rev() {
+        bounds[pos] = bounds[pos + 1] + candidates[pos][0].0;
+    }
+    bounds
+}
+
+/// Find the most likely legal cube for the color probabilities of the stickers (see the header of this file)
+pub fn nearest_legal_state(probabilities: &ColorProbabilities) -> Correction {
+    let corner_candidates = candidates(probabilities, &CORNER_FACELET, &CORNER_COLOR);
+    let edge_candidates = candidates(probabilities, &EDGE_FACELET, &EDGE_COLOR);
+    let edge_bounds = bounds(&edge_candidates, 0.0);
+    let corner_bounds = bounds(&corner_candidates, edge_bounds[0]);
+    let center_cost: f32 = (0..N_COLORS).map(|face| color_cost(probabilities, 9 * face + 4, Color::from(face))).sum();
+
+    let mut search = Search {
+        corner_candidates,
+        edge_candidates,
+        corner_bounds,
+        edge_bounds,
+        corners: [(0, 0); 8],
+        edges: [(0, 0); 12],
+        best: None,
+    };
+    search.place_corner(0, 0, center_cost);
+    let cube = search.best.expect("the solved cube is always a candidate").0.to_facelet_cube();
+
+    let changed = (0..54)
+        .filter(|&index| index % 9 != 4 && cube.get_facelet(index) != best_color(probabilities, index))
+        .collect();
+    Correction { cube, changed, cost: cube_cost(probabilities, &cube) }
+}
+
+/// The branch and bound search of nearest_legal_state
+struct Search {
+    corner_candidates: Vec<Vec<(f32, usize, u8)>>,
+    edge_candidates: Vec<Vec<(f32, usize, u8)>>,
+    /// The cheapest cost of the corners from each position, plus the cheapest cost of all the edges
+    corner_bounds: Vec<f32>,
+    /// The cheapest cost of the edges from each position
+    edge_bounds: Vec<f32>,
+    corners: [(usize, u8); 8],
+    edges: [(usize, u8); 12],
+    /// The best legal cube found so far, and its cost
+    best: Option<(Cube, f32)>,
+}
+
+impl Search {
+    /// Return true if a branch of cost `cost` can not be cheaper than the best legal cube found so far
+    fn cut(&self, cost: f32) -> bool {
+        self.best.as_ref().is_some_and(|&(_, best)| cost >= best)
+    }
+
+    /// Place a corner at the position `pos`, `used` is the set (as bits) of the corners already placed and `cost`
+    /// the cost of the cubies already placed
+    fn place_corner(&mut self, pos: usize, used: u32, cost: f32) {
+        if pos == N_CORNERS {
+            let twist: usize = self.corners.iter().map(|&(_, o)| o as usize).sum();
+            if twist.is_multiple_of(3) {
+                self.place_edge(0, 0, cost);
+            }
+            return;
+        }
+        for c in 0..self.corner_candidates[pos].len() {
+            let (corner_cost, corner, ori) = self.corner_candidates[pos][c];
+            // The candidates are sorted, so the next ones are cut too
+            if self.cut(cost + corner_cost + self.corner_bounds[pos + 1]) {
+                return;
+            }
+            if used & (1 << corner) == 0 {
+                self.corners[pos] = (corner, ori);
+                self.place_corner(pos + 1, used | (1 << corner), cost + corner_cost);
+            }
+        }
+    }
+
+    /// Place an edge at the position `pos`, `used` is the set (as bits) of the edges already placed and `cost`
+    /// the cost of the cubies already placed
+    fn place_edge(&mut self, pos: usize, used: u32, cost: f32) {
+        if pos == N_EDGES {
+            let mut cp = CP_DEFAULT;
+            let mut co = CO_DEFAULT;
+            let mut ep = EP_DEFAULT;
+            let mut eo = EO_DEFAULT;
+            for (i, &(corner, ori)) in self.corners.iter().enumerate() {
+                cp[i] = Corner::from(corner);
+                co[i] = ori;
+            }
+            for (i, &(edge, ori)) in self.edges.iter().enumerate() {
+                ep[i] = Edge::from(edge);
+                eo[i] = ori;
+            }
+            let cube = Cube::new(cp, co, ep, eo);
+            if cube.check().is_ok() {
+                self.best = Some((cube, cost));
+            }
+            return;
+        }
+        for e in 0..self.edge_candidates[pos].len() {
+            let (edge_cost, edge, ori) = self.edge_candidates[pos][e];
+            if self.cut(cost + edge_cost + self.edge_bounds[pos + 1]) {
+                return;
+            }
+            if used & (1 << edge) == 0 {
+                self.edges[pos] = (edge, ori);
+                self.place_edge(pos + 1, used | (1 << edge), cost + edge_cost);
+            }
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use super::defs::*;
-use super::enums::{ Corner, Edge };
+use super::enums::{ Corner, CubeError, Edge };
 use super::face_cube::*;
+use std::cmp::Ordering;
 use std::fmt; // Usef for impl display
+use std::hash::{ Hash, Hasher };
 use rand::Rng;
 
 #[derive(Debug, Clone, Copy)]
@@ -63,21 +65,18 @@ impl Cube {
                 }
             } else if ori_self < 3 && ori_other >= 3 {
                 // cube b is in a mirrored state
-                println!("not rotating");
                 current_ori = ori_self + ori_other;
                 if current_ori >= 6 {
                     current_ori -= 3;
                 }
             } else if ori_self >= 3 && ori_other < 3 {
                 // cube a is in a mirrored state
-                println!("not rotating");
                 current_ori = ori_self - ori_other;
                 if current_ori < 3 {
                     current_ori += 3;
                 }
             } else if ori_self >= 3 && ori_other >= 3 {
                 // if both cubes are in mirrored states
-                println!("not rotating");
                 current_ori = ori_self - ori_other;
                 if current_ori < 0 {
                     current_ori += 3;
@@ -165,11 +164,11 @@ impl Cube {
     ///Return a facelet representation of the cube.
     pub fn to_facelet_cube(self) -> FaceCube {
         let mut face_cube = FaceCube::new();
-        for color in 0..N_COLORS {
-            let corner = self.cp[color as usize]; // corner j is at corner position i
-            let orientation = self.co[color as usize]; // orientation of C j at position i
+        for corner_pos in 0..N_CORNERS {
+            let corner = self.cp[corner_pos]; // corner j is at corner position i
+            let orientation = self.co[corner_pos]; // orientation of C j at position i
             for k in 0..3 {
-                let index = CORNER_FACELET[color as usize]
+                let index = CORNER_FACELET[corner_pos]
                     [((k + orientation) % 3) as usize] as usize;
                 let new_color = CORNER_COLOR[corner as usize][k as usize];
                 face_cube.set_facelet(index, new_color);
@@ -192,7 +191,7 @@ impl Cube {
     pub fn corner_parity(self) -> u8 {
         let mut parity = 0;
         for corner in (0..N_CORNERS).rev() {
-            for permutation in (corner..=0).rev() {
+            for permutation in (0..corner).rev() {
                 if (self.cp[permutation as usize] as u8) > (self.cp[corner as usize] as u8) {
                     parity += 1;
                 }
@@ -214,55 +213,69 @@ impl Cube {
         parity % 2
     }
 
-    ///Check if cubiecube is valid.
-    pub fn verify(&self) -> bool {
+    ///Check if cubiecube is valid, and if not, tell why.
+    pub fn check(&self) -> Result<(), CubeError> {
         let mut edge_count = [0; 12];
         for i in 0..N_EDGES {
-            edge_count[self.ep[i as usize] as usize] += 1;
+            edge_count[self.ep[i] as usize] += 1;
         }
-        for edge in 0..N_EDGES {
-            if edge_count[edge as usize] != 1 {
-                println!("Some edges are invalid ...");
-                return false;
-            }
+        if edge_count.iter().any(|&count| count != 1) {
+            return Err(CubeError::InvalidEdges);
         }
 
-        let mut verify_edge = 0;
-        for edge in 0..N_EDGES {
-            verify_edge += self.eo[edge as usize];
+        if self.eo.iter().any(|&ori| ori > 1) || self.co.iter().any(|&ori| ori > 2) {
+            return Err(CubeError::InvalidOrientation);
         }
+
+        let verify_edge: u8 = self.eo.iter().sum();
         if verify_edge % 2 != 0 {
-            println!("Total edge flip is wrong ...");
-            return false;
+            return Err(CubeError::EdgeFlip);
         }
 
         let mut corner_count = [0; 8];
-        for corner in 0..N_CORNERS {
-            corner_count[self.cp[corner as usize] as usize] += 1;
+        for i in 0..N_CORNERS {
+            corner_count[self.cp[i] as usize] += 1;
         }
-        for corner in 0..N_CORNERS {
-            if corner_count[corner as usize] != 1 {
-                println!("Some corners are invalid ...");
-                return false;
-            }
-        }
-
-        let mut verify_corner = 0;
-        for corner in 0..N_COLORS {
-            verify_corner += self.co[corner as usize];
+        if corner_count.iter().any(|&count| count != 1) {
+            return Err(CubeError::InvalidCorners);
         }
 
+        let verify_corner: u8 = self.co.iter().sum();
         if verify_corner % 3 != 0 {
-            println!("Total corner flip is wrong...");
-            return false;
+            return Err(CubeError::CornerTwist);
         }
 
         if self.edge_parity() != self.corner_parity() {
-            println!("Wrong egde and corner parity...");
-            return false;
+            return Err(CubeError::Parity);
         }
 
-        return true;
+        Ok(())
+    }
+
+    ///Check if cubiecube is valid.
+    pub fn verify(&self) -> bool {
+        match self.check() {
+            Ok(()) => true,
+            Err(error) => {
+                println!("{}", error);
+                false
+            }
+        }
+    }
+
+    /// Return the conjugate S^-1 * self * S of the cube by the symmetry S = ALL_SYMMETRIES[sym].
+    /// The result is the same state seen from another point of view (or in a mirror).
+    pub fn conjugate(&self, sym: usize) -> Cube {
+        let mut cube = ALL_SYMMETRIES[SYM_INV[sym]];
+        cube.multiply(*self);
+        cube.multiply(ALL_SYMMETRIES[sym]);
+        cube
+    }
+
+    /// Return the lexicographically smallest of the 48 symmetry conjugates of the cube.
+    /// Two states that only differ by a rotation or a reflection have the same canonical form.
+    pub fn canonical(&self) -> Cube {
+        (0..N_SYM).map(|sym| self.conjugate(sym)).min().unwrap()
     }
 
     pub fn randomize(&mut self) {
@@ -303,3 +316,28 @@ impl PartialEq for Cube {
         self.cp == other.cp && self.co == other.co && self.ep == other.ep && self.eo == other.eo
     }
 }
+
+impl Eq for Cube {}
+
+/// Hash the same fields as PartialEq, allow to use Cube as a HashMap/HashSet key
+impl Hash for Cube {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.cp.hash(state);
+        self.co.hash(state);
+        self.ep.hash(state);
+        self.eo.hash(state);
+    }
+}
+
+/// Lexicographic order on (cp, co, ep, eo), allow to use Cube as a BTreeMap/BTreeSet key
+impl Ord for Cube {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.cp, self.co, self.ep, self.eo).cmp(&(other.cp, other.co, other.ep, other.eo))
+    }
+}
+
+impl PartialOrd for Cube {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
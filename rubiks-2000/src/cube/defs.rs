@@ -1,5 +1,5 @@
 use super::cube::Cube;
-use super::enums::{ Color as Cl, Corner as Co, Edge as Ed, Facelet as Fc };
+use super::enums::{ Color as Cl, Corner as Co, Edge as Ed, Facelet as Fc, BS };
 use lazy_static::lazy_static; // Used to create ALL_MOVES (because we cannot initialize with a not const functions a const at runtime)
 
 //////////////////////// Some constants about Rubiks' Cube ////////////////////////////////////////////////////////////////
@@ -14,6 +14,7 @@ pub const N_EDGES: usize = 12;
 pub const N_CORNERS: usize = 8;
 pub const N_MOVES: usize = 18;
 pub const N_BASE_MOVES: usize = 6;
+pub const N_SYM: usize = 48;
 
 /////////////////////// The default permutations and orientations of a cube ///////////////////////////////////////////////
 
@@ -210,6 +211,144 @@ lazy_static! {
     pub static ref ALL_MOVES: [Cube; 18] = initialize_all_moves();
 }
 
+////////////////////// The permutations and orientations to create the basic symmetries //////////////////////////////////
+
+// 120° clockwise rotation around the long diagonal URF-DBL
+pub const CP_ROT_URF3: [Co; 8] = [Co::URF, Co::DFR, Co::DLF, Co::UFL, Co::UBR, Co::DRB, Co::DBL, Co::ULB];
+pub const CO_ROT_URF3: [u8; 8] = [1, 2, 1, 2, 2, 1, 2, 1];
+pub const EP_ROT_URF3: [Ed; 12] = [
+    Ed::UF,
+    Ed::FR,
+    Ed::DF,
+    Ed::FL,
+    Ed::UB,
+    Ed::BR,
+    Ed::DB,
+    Ed::BL,
+    Ed::UR,
+    Ed::DR,
+    Ed::DL,
+    Ed::UL,
+];
+pub const EO_ROT_URF3: [u8; 12] = [1, 0, 1, 0, 1, 0, 1, 0, 1, 1, 1, 1];
+
+// 180° rotation around the axis through the F and B centers
+pub const CP_ROT_F2: [Co; 8] = [Co::DLF, Co::DFR, Co::DRB, Co::DBL, Co::UFL, Co::URF, Co::UBR, Co::ULB];
+pub const CO_ROT_F2: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 0];
+pub const EP_ROT_F2: [Ed; 12] = [
+    Ed::DL,
+    Ed::DF,
+    Ed::DR,
+    Ed::DB,
+    Ed::UL,
+    Ed::UF,
+    Ed::UR,
+    Ed::UB,
+    Ed::FL,
+    Ed::FR,
+    Ed::BR,
+    Ed::BL,
+];
+pub const EO_ROT_F2: [u8; 12] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+// 90° clockwise rotation around the axis through the U and D centers
+pub const CP_ROT_U4: [Co; 8] = [Co::UBR, Co::URF, Co::UFL, Co::ULB, Co::DRB, Co::DFR, Co::DLF, Co::DBL];
+pub const CO_ROT_U4: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 0];
+pub const EP_ROT_U4: [Ed; 12] = [
+    Ed::UB,
+    Ed::UR,
+    Ed::UF,
+    Ed::UL,
+    Ed::DB,
+    Ed::DR,
+    Ed::DF,
+    Ed::DL,
+    Ed::BR,
+    Ed::FR,
+    Ed::FL,
+    Ed::BL,
+];
+pub const EO_ROT_U4: [u8; 12] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1];
+
+// Reflection at the plane through the U, D, F, B centers
+// (an orientation >= 3 marks a corner of a mirrored cube, see Cube::corner_multiply)
+pub const CP_MIRR_LR2: [Co; 8] = [Co::UFL, Co::URF, Co::UBR, Co::ULB, Co::DLF, Co::DFR, Co::DRB, Co::DBL];
+pub const CO_MIRR_LR2: [u8; 8] = [3, 3, 3, 3, 3, 3, 3, 3];
+pub const EP_MIRR_LR2: [Ed; 12] = [
+    Ed::UL,
+    Ed::UF,
+    Ed::UR,
+    Ed::UB,
+    Ed::DL,
+    Ed::DF,
+    Ed::DR,
+    Ed::DB,
+    Ed::FL,
+    Ed::FR,
+    Ed::BR,
+    Ed::BL,
+];
+pub const EO_MIRR_LR2: [u8; 12] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+////////////////////// The basic symmetries represented with cubes /////////////////////////////////////////////////////////
+
+// The four basic symmetries, indexed by the BS enum
+pub const BASIC_SYMMETRIES: [Cube; 4] = [
+    Cube::new(CP_ROT_URF3, CO_ROT_URF3, EP_ROT_URF3, EO_ROT_URF3),
+    Cube::new(CP_ROT_F2, CO_ROT_F2, EP_ROT_F2, EO_ROT_F2),
+    Cube::new(CP_ROT_U4, CO_ROT_U4, EP_ROT_U4, EO_ROT_U4),
+    Cube::new(CP_MIRR_LR2, CO_MIRR_LR2, EP_MIRR_LR2, EO_MIRR_LR2),
+];
+
+// Initialize the ALL_SYMMETRIES static value
+// Symmetry 16 * urf3 + 8 * f2 + 2 * u4 + lr2 is ROT_URF3^urf3 * ROT_F2^f2 * ROT_U4^u4 * MIRR_LR2^lr2
+fn initialize_all_symmetries() -> [Cube; N_SYM] {
+    let mut all_sym_tab = [DEFAULT; N_SYM];
+    let mut cube = DEFAULT;
+    let mut index = 0;
+
+    for _urf3 in 0..3 {
+        for _f2 in 0..2 {
+            for _u4 in 0..4 {
+                for _lr2 in 0..2 {
+                    all_sym_tab[index] = cube;
+                    index += 1;
+                    cube.multiply(BASIC_SYMMETRIES[BS::MirrLR2 as usize]);
+                }
+                cube.multiply(BASIC_SYMMETRIES[BS::RotU4 as usize]);
+            }
+            cube.multiply(BASIC_SYMMETRIES[BS::RotF2 as usize]);
+        }
+        cube.multiply(BASIC_SYMMETRIES[BS::RotURF3 as usize]);
+    }
+
+    all_sym_tab
+}
+
+// Initialize the SYM_INV static value: SYM_INV[s] is the index of the inverse of the symmetry s
+fn initialize_sym_inv() -> [usize; N_SYM] {
+    let mut sym_inv = [0; N_SYM];
+
+    for (s, sym) in ALL_SYMMETRIES.iter().enumerate() {
+        for (t, other) in ALL_SYMMETRIES.iter().enumerate() {
+            let mut cube = *sym;
+            cube.multiply(*other);
+            if cube == DEFAULT {
+                sym_inv[s] = t;
+                break;
+            }
+        }
+    }
+
+    sym_inv
+}
+
+// ALL_SYMMETRIES contains the 48 symmetries of the cube (24 rotations and their mirrored version)
+lazy_static! {
+    pub static ref ALL_SYMMETRIES: [Cube; N_SYM] = initialize_all_symmetries();
+    pub static ref SYM_INV: [usize; N_SYM] = initialize_sym_inv();
+}
+
 //Map the corner positions to facelet positions.
 pub const CORNER_FACELET: [[Fc; 3]; 8] = [
     [Fc::U9, Fc::R1, Fc::F3],
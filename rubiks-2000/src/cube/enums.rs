@@ -86,7 +86,8 @@ pub enum Facelet {
     B9 = 53,
 }
 
-#[derive(Debug, PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     //The possible colors of the cube facelets. Color U refers to the color of the U(p)-face etc.
     //Also used to name the faces itself
@@ -98,7 +99,29 @@ pub enum Color {
     B = 5,
 }
 
-#[derive(Debug, PartialEq, Copy, Clone)]
+// Implementation of the `From<usize>` trait for the `Color` enum.
+impl From<usize> for Color {
+    fn from(value: usize) -> Self {
+        match value {
+            0 => Color::U,
+            1 => Color::R,
+            2 => Color::F,
+            3 => Color::D,
+            4 => Color::L,
+            5 => Color::B,
+            _ => panic!("Invalid color index"),
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Corner {
     //The names of the corner positions of the cube. Corner URF e.g. has an U(p), a R(ight) and a F(ront) facelet.
     URF = 0,
@@ -158,7 +181,8 @@ impl fmt::Display for Corner {
     }
 }
 
-#[derive(Debug, PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 //The names of the edge positions of the cube. Edge UR e.g. has an U(p) and R(ight) facelet.
 pub enum Edge {
     UR = 0,
@@ -355,3 +379,30 @@ pub enum BS {
     RotU4 = 2,
     MirrLR2 = 3,
 }
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum CubeError {
+    //The reasons why a cubie cube can be unsolvable, see Cube::check
+    InvalidEdges = 0,
+    InvalidOrientation = 1,
+    EdgeFlip = 2,
+    InvalidCorners = 3,
+    CornerTwist = 4,
+    Parity = 5,
+}
+
+impl fmt::Display for CubeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            CubeError::InvalidEdges => "Some edges are invalid ...",
+            CubeError::InvalidOrientation => "Some orientations are out of range ...",
+            CubeError::EdgeFlip => "Total edge flip is wrong ...",
+            CubeError::InvalidCorners => "Some corners are invalid ...",
+            CubeError::CornerTwist => "Total corner flip is wrong...",
+            CubeError::Parity => "Wrong egde and corner parity...",
+        };
+        write!(f, "{}", message)
+    }
+}
+
+impl std::error::Error for CubeError {}
@@ -1,7 +1,10 @@
 use super::cube::*;
 use super::defs::*;
 use super::enums::*;
-#[derive(Debug, Clone, Copy, PartialEq)]
+use std::str::FromStr;
+
+// Debug is implemented in render::terminal, to draw the net of the cube
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct FaceCube {
     facelets: [Color; 54],
 }
@@ -28,52 +31,13 @@ impl FaceCube {
         FaceCube { facelets }
     }
     ///transform a string of the cube into this struct
+    /// # Panics :
+    /// If the string is not a valid facelet string, see the FromStr implementation to handle the error.
     pub fn from_string(str_cube: &str) -> Self {
-        if str_cube.len() < 54 {
-            panic!("pas le bon nombre de facelets");
-        }
-        if str_cube.len() > 54 {
-            panic!("pas le bon nombre de facelets");
+        match str_cube.parse() {
+            Ok(face_cube) => face_cube,
+            Err(error) => panic!("{}", error),
         }
-
-        let mut facelets = [Color::B; 54];
-        let mut cpt = [0; 6];
-        for (index, color) in str_cube.char_indices() {
-            match color {
-                'U' => {
-                    facelets[index] = Color::U;
-                    cpt[0] += 1
-                }
-                'B' => {
-                    facelets[index] = Color::B;
-                    cpt[1] += 1
-                }
-                'F' => {
-                    facelets[index] = Color::F;
-                    cpt[2] += 1
-                }
-                'D' => {
-                    facelets[index] = Color::D;
-                    cpt[3] += 1
-                }
-                'L' => {
-                    facelets[index] = Color::L;
-                    cpt[4] += 1
-                }
-                'R' => {
-                    facelets[index] = Color::R;
-                    cpt[5] += 1
-                }
-                _ => panic!("pas les bonnes lettres"),
-            }
-        }
-        // verify if the correct number of each color was provide
-        for i in 0..6 {
-            if cpt[i] != 9 {
-                panic!("probleme de couleur");
-            }
-        }
-        FaceCube { facelets }
     }
 
     ///inverse of the previous fonction
@@ -143,7 +107,63 @@ impl FaceCube {
         Cube::new(cp, co, ep, eo)
     }
 
+    /// Apply a move to the cube
+    pub fn apply_move(&mut self, mv: Move) {
+        let mut cube = self.to_cubie_cube();
+        cube.multiply(mv.get_cube());
+        *self = cube.to_facelet_cube();
+    }
+
+    /// Return the lexicographically smallest (facelet by facelet) of the 48 symmetry conjugates of the cube.
+    pub fn canonical(self) -> Self {
+        let cube = self.to_cubie_cube();
+        (0..N_SYM).map(|sym| cube.conjugate(sym).to_facelet_cube()).min().unwrap()
+    }
+
+    /// Returns the color of the facelet at position index (see the Facelet enum)
+    pub fn get_facelet(&self, index: usize) -> Color {
+        self.facelets[index]
+    }
+
+    /// Returns the colors of all the facelets, in the order of the Facelet enum
+    pub fn get_facelets(&self) -> [Color; 54] {
+        self.facelets
+    }
+
     pub fn set_facelet(&mut self, index: usize, new_color: Color) {
         self.facelets[index] = new_color;
     }
 }
+
+/// Parse a facelet string ("UUUUUUUUURRR...") in the order of the Facelet enum
+impl FromStr for FaceCube {
+    type Err = String;
+
+    fn from_str(str_cube: &str) -> Result<Self, Self::Err> {
+        if str_cube.chars().count() != 54 {
+            return Err(String::from("pas le bon nombre de facelets"));
+        }
+
+        let mut facelets = [Color::B; 54];
+        let mut cpt = [0; 6];
+        for (index, color) in str_cube.chars().enumerate() {
+            facelets[index] = match color {
+                'U' => Color::U,
+                'R' => Color::R,
+                'F' => Color::F,
+                'D' => Color::D,
+                'L' => Color::L,
+                'B' => Color::B,
+                _ => {
+                    return Err(format!("pas les bonnes lettres: {}", color));
+                }
+            };
+            cpt[facelets[index] as usize] += 1;
+        }
+        // verify if the correct number of each color was provide
+        if cpt.iter().any(|&count| count != 9) {
+            return Err(String::from("probleme de couleur"));
+        }
+        Ok(FaceCube { facelets })
+    }
+}
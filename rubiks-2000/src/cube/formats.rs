@@ -0,0 +1,197 @@
+// Alternative text formats of a cube state, each one with its parser and its printer:
+//
+// * Color letters: the 54 facelets in the order of the Facelet enum, written with the physical sticker colors
+//   (W/Y/R/O/G/B). The colors are mapped to the faces through the center stickers.
+// * ASCII net: the 2D unfolded cube drawn in the Facelet enum doc comment.
+// * Reid notation: "UF UR UB UL DF DR DB DL FR FL BR BL UFR URB UBL ULF DRF DFL DLB DBR", the colors of the
+//   stickers of the 12 edges and the 8 corners (format used by Michael Reid's optimal solver).
+
+use super::defs::*;
+use super::enums::{ Color, Facelet as Fc };
+use super::face_cube::FaceCube;
+
+/// The letters of the faces, indexed by the Color enum
+pub const FACE_LETTERS: [char; 6] = ['U', 'R', 'F', 'D', 'L', 'B'];
+
+/// The usual western color scheme (white up, green front), indexed by the Color enum
+pub const WESTERN_COLORS: [char; 6] = ['W', 'R', 'G', 'Y', 'O', 'B'];
+
+/// The centers facelets, indexed by the Color enum
+const CENTERS: [Fc; 6] = [Fc::U5, Fc::R5, Fc::F5, Fc::D5, Fc::L5, Fc::B5];
+
+/// The faces of the three bands of the net, from left to right
+const NET_TOP: [Color; 1] = [Color::U];
+const NET_MIDDLE: [Color; 4] = [Color::L, Color::F, Color::R, Color::B];
+const NET_BOTTOM: [Color; 1] = [Color::D];
+
+/// The 12 edges and the 8 corners in the order of the Reid notation
+const REID_EDGES: [&str; 12] = ["UF", "UR", "UB", "UL", "DF", "DR", "DB", "DL", "FR", "FL", "BR", "BL"];
+const REID_CORNERS: [&str; 8] = ["UFR", "URB", "UBL", "ULF", "DRF", "DFL", "DLB", "DBR"];
+
+/// Return the facelets of the cubie named `name` (ex: "UFR"), in the order of the letters of the name
+fn reid_facelets(name: &str) -> Vec<usize> {
+    let candidates: Vec<Vec<usize>> = if name.len() == 2 {
+        EDGE_FACELET.iter()
+            .map(|facelets| facelets.iter().map(|&f| f as usize).collect())
+            .collect()
+    } else {
+        CORNER_FACELET.iter()
+            .map(|facelets| facelets.iter().map(|&f| f as usize).collect())
+            .collect()
+    };
+
+    // A facelet belongs to the face index / 9
+    let face_letter = |facelet: &usize| FACE_LETTERS[facelet / 9];
+    let cubie = candidates
+        .into_iter()
+        .find(|facelets| name.chars().all(|c| facelets.iter().any(|f| face_letter(f) == c)))
+        .unwrap();
+
+    name.chars()
+        .map(|c| *cubie.iter().find(|f| face_letter(f) == c).unwrap())
+        .collect()
+}
+
+impl FaceCube {
+    /// Create a FaceCube from the 54 sticker colors, in the order of the Facelet enum.
+    /// Any six distinct letters can be used (ex: "WWWWWWWWWRRRR..." or "UUUUUUUUURRRR..."): the letter of each
+    /// center sticker gives the face of this color. The letters are case insensitive.
+    pub fn from_color_letters(colors: &str) -> Result<Self, String> {
+        let colors: Vec<char> = colors.chars().map(|c| c.to_ascii_uppercase()).collect();
+        if colors.len() != 54 {
+            return Err(format!("Expected 54 stickers, found {}", colors.len()));
+        }
+
+        let center_letters: Vec<char> = CENTERS.iter().map(|&c| colors[c as usize]).collect();
+        for (i, letter) in center_letters.iter().enumerate() {
+            if center_letters[..i].contains(letter) {
+                return Err(format!("Two centers have the same color {}", letter));
+            }
+        }
+
+        let facelets: Result<String, String> = colors
+            .iter()
+            .map(|c| {
+                match center_letters.iter().position(|center| center == c) {
+                    Some(face) => Ok(FACE_LETTERS[face]),
+                    None => Err(format!("The color {} is not the color of a center", c)),
+                }
+            })
+            .collect();
+        facelets?.parse()
+    }
+
+    /// Write the 54 sticker colors in the order of the Facelet enum, using `colors` (indexed by the Color enum)
+    /// # Example :
+    /// cube.to_color_letters(&WESTERN_COLORS) -> "WWWWWWWWWRRRRRRRRRGGGGGGGGGYYYYYYYYYOOOOOOOOOBBBBBBBBB"
+    pub fn to_color_letters(self, colors: &[char; 6]) -> String {
+        self.get_facelets()
+            .iter()
+            .map(|&color| colors[color as usize])
+            .collect()
+    }
+
+    /// Create a FaceCube from a 2D unfolded net:
+    /// ```text
+    ///     UUU
+    ///     UUU
+    ///     UUU
+    /// LLL FFF RRR BBB
+    /// LLL FFF RRR BBB
+    /// LLL FFF RRR BBB
+    ///     DDD
+    ///     DDD
+    ///     DDD
+    /// ```
+    /// Only the letters are read, so the stickers can be separated by spaces or drawn in boxes like in the Facelet
+    /// enum doc comment, and the lines without any letter are ignored. The letters can be face letters or colors,
+    /// see from_color_letters.
+    pub fn from_net(net: &str) -> Result<Self, String> {
+        let rows: Vec<Vec<char>> = net
+            .lines()
+            .map(|line| line.chars().filter(|c| c.is_ascii_alphabetic()).collect::<Vec<char>>())
+            .filter(|row| !row.is_empty())
+            .collect();
+        if rows.len() != 9 {
+            return Err(format!("Expected 9 rows of stickers, found {}", rows.len()));
+        }
+
+        let mut colors = [' '; 54];
+        for (i, row) in rows.iter().enumerate() {
+            let faces: &[Color] = match i / 3 {
+                0 => &NET_TOP,
+                1 => &NET_MIDDLE,
+                _ => &NET_BOTTOM,
+            };
+            if row.len() != 3 * faces.len() {
+                return Err(format!("Expected {} stickers in row {}, found {}", 3 * faces.len(), i + 1, row.len()));
+            }
+            for (j, &color) in row.iter().enumerate() {
+                let face = faces[j / 3] as usize;
+                colors[9 * face + 3 * (i % 3) + j % 3] = color;
+            }
+        }
+
+        Self::from_color_letters(&colors.iter().collect::<String>())
+    }
+
+    /// Write the cube as a 2D unfolded net (see from_net), using `colors` (indexed by the Color enum)
+    pub fn to_net(self, colors: &[char; 6]) -> String {
+        let letters: Vec<char> = self.to_color_letters(colors).chars().collect();
+        let mut net = String::new();
+
+        for faces in [&NET_TOP[..], &NET_MIDDLE[..], &NET_BOTTOM[..]] {
+            for row in 0..3 {
+                let blocks: Vec<String> = faces
+                    .iter()
+                    .map(|&face| {
+                        let start = 9 * (face as usize) + 3 * row;
+                        letters[start..start + 3].iter().collect()
+                    })
+                    .collect();
+                if faces.len() == 1 {
+                    net.push_str("    ");
+                }
+                net.push_str(&blocks.join(" "));
+                net.push('\n');
+            }
+        }
+        net
+    }
+
+    /// Create a FaceCube from the Reid notation: the colors (as face letters) of the 12 edges and 8 corners,
+    /// ex: "UF UR UB UL DF DR DB DL FR FL BR BL UFR URB UBL ULF DRF DFL DLB DBR" for the solved cube.
+    /// Each sticker is given in the order of the letters of the position name.
+    pub fn from_reid(reid: &str) -> Result<Self, String> {
+        let cubies: Vec<&str> = reid.split_whitespace().collect();
+        if cubies.len() != 20 {
+            return Err(format!("Expected 20 cubies, found {}", cubies.len()));
+        }
+
+        let mut colors = FaceCube::new().to_color_letters(&FACE_LETTERS).chars().collect::<Vec<char>>();
+        for (name, cubie) in REID_EDGES.iter().chain(REID_CORNERS.iter()).zip(cubies) {
+            if cubie.chars().count() != name.len() {
+                return Err(format!("The cubie {} at position {} has the wrong number of stickers", cubie, name));
+            }
+            for (facelet, color) in reid_facelets(name).into_iter().zip(cubie.chars()) {
+                colors[facelet] = color.to_ascii_uppercase();
+            }
+        }
+
+        colors.iter().collect::<String>().parse()
+    }
+
+    /// Write the cube in the Reid notation (see from_reid)
+    pub fn to_reid(self) -> String {
+        REID_EDGES.iter()
+            .chain(REID_CORNERS.iter())
+            .map(|name| {
+                reid_facelets(name)
+                    .into_iter()
+                    .map(|facelet| FACE_LETTERS[self.get_facelet(facelet) as usize])
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
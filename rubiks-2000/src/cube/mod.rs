@@ -1,4 +1,10 @@
+pub mod correction;
 pub mod cube;
 pub mod defs;
 pub mod enums;
 pub mod face_cube;
+pub mod formats;
+pub mod notation;
+pub mod partial;
+#[cfg(feature = "serde")]
+pub mod serialize;
\ No newline at end of file
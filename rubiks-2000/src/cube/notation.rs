@@ -0,0 +1,75 @@
+use super::enums::Move;
+use std::str::FromStr;
+
+// The faces in the order of the Move enum (U1 = 0, R1 = 3, F1 = 6, ...)
+const FACES: [char; 6] = ['U', 'R', 'F', 'D', 'L', 'B'];
+
+impl Move {
+    /// Return the move in the standard notation: "U" for U1, "U2" for U2 and "U'" for U3.
+    /// # Panics :
+    /// On Move::Default, which is not a real move.
+    pub fn to_notation(self) -> String {
+        if self == Move::Default {
+            panic!("Move::Default has no notation");
+        }
+        let face = FACES[(self as usize) / 3];
+        match (self as usize) % 3 {
+            0 => face.to_string(),
+            1 => format!("{}2", face),
+            _ => format!("{}'", face),
+        }
+    }
+}
+
+/// Parse a move written in the standard notation ("R", "R2", "R'") or as displayed by Move ("R1", "R2", "R3").
+/// "R2'" is accepted as a synonym of "R2".
+impl FromStr for Move {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let face = chars.next().ok_or("Empty move")?;
+        let face_index = FACES
+            .iter()
+            .position(|&f| f == face)
+            .ok_or(format!("Unknown face in move \"{}\"", s))?;
+        let power = match chars.as_str() {
+            "" | "1" => 0,
+            "2" | "2'" | "2’" => 1,
+            "'" | "’" | "3" => 2,
+            _ => {
+                return Err(format!("Unknown suffix in move \"{}\"", s));
+            }
+        };
+        Ok(Move::from(3 * face_index + power))
+    }
+}
+
+/// Parse an algorithm written in the standard notation, moves separated by whitespaces.
+/// # Example :
+/// parse_algorithm("R U R' U'") -> [R1, U1, R3, U3]
+pub fn parse_algorithm(algorithm: &str) -> Result<Vec<Move>, String> {
+    algorithm.split_whitespace().map(str::parse).collect()
+}
+
+/// Write an algorithm in the standard notation, inverse of parse_algorithm
+pub fn algorithm_to_string(moves: &[Move]) -> String {
+    moves
+        .iter()
+        .map(|mv| mv.to_notation())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Count the moves of an algorithm in the half turn metric (a U2 counts for one move)
+pub fn htm_length(moves: &[Move]) -> usize {
+    moves.len()
+}
+
+/// Count the moves of an algorithm in the quarter turn metric (a U2 counts for two moves)
+pub fn qtm_length(moves: &[Move]) -> usize {
+    moves
+        .iter()
+        .map(|mv| if (*mv as usize) % 3 == 1 { 2 } else { 1 })
+        .sum()
+}
@@ -0,0 +1,259 @@
+// Partial facelet cubes: some stickers are unknown (missed by the camera, or a face which was not scanned).
+// The resolver enumerates every legal cube which matches the known stickers, by placing the cubies one by one:
+// a cubie can only go at a position if its colors (CORNER_COLOR / EDGE_COLOR) match the known stickers there.
+
+use super::cube::Cube;
+use super::defs::*;
+use super::enums::{ Color, Corner, Edge };
+use super::face_cube::FaceCube;
+use std::str::FromStr;
+
+/// The maximal number of completions enumerated by PartialFaceCube::resolve
+pub const MAX_COMPLETIONS: usize = 1000;
+
+/// A facelet cube in which some stickers can be unknown (None)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PartialFaceCube {
+    facelets: [Option<Color>; 54],
+}
+
+/// The result of the resolution of a partial cube
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resolution {
+    /// No legal cube matches the known stickers
+    Impossible,
+    /// Exactly one legal cube matches the known stickers
+    Unique(FaceCube),
+    /// Several legal cubes match the known stickers
+    Ambiguous {
+        /// The legal cubes found
+        completions: Vec<FaceCube>,
+        /// The unknown facelets which differ between the completions, the most discriminating first:
+        /// these are the stickers to scan again to remove the ambiguity
+        stickers: Vec<usize>,
+        /// True if the search stopped at MAX_COMPLETIONS, so other completions may exist
+        truncated: bool,
+    },
+}
+
+impl PartialFaceCube {
+    /// Create a partial cube where only the centers are known
+    pub fn new() -> Self {
+        let mut facelets = [None; 54];
+        for face in 0..N_COLORS {
+            facelets[9 * face + 4] = Some(Color::from(face));
+        }
+        Self { facelets }
+    }
+
+    /// Returns the color of the facelet at position index, None if it is unknown
+    pub fn get_facelet(&self, index: usize) -> Option<Color> {
+        self.facelets[index]
+    }
+
+    /// Set the color of the facelet at position index, None if it is unknown
+    pub fn set_facelet(&mut self, index: usize, color: Option<Color>) {
+        self.facelets[index] = color;
+    }
+
+    /// Returns the number of unknown stickers
+    pub fn unknown_count(&self) -> usize {
+        self.facelets.iter().filter(|f| f.is_none()).count()
+    }
+
+    /// Return true if the cubie at the facelets `position` can be the cubie of colors `colors`
+    /// with the orientation `ori` (same convention as Cube::to_facelet_cube)
+    fn matches(&self, position: &[usize], colors: &[Color], ori: usize) -> bool {
+        let n = position.len();
+        (0..n).all(|k| {
+            match self.facelets[position[(k + ori) % n]] {
+                Some(color) => color == colors[k],
+                None => true,
+            }
+        })
+    }
+
+    /// Return the legal cubes which match the known stickers, at most `limit` of them
+    pub fn completions(&self, limit: usize) -> Vec<FaceCube> {
+        // The possible (cubie, orientation) for each position
+        let corner_candidates: Vec<Vec<(usize, u8)>> = (0..N_CORNERS)
+            .map(|i| {
+                let position: Vec<usize> = CORNER_FACELET[i].iter().map(|&f| f as usize).collect();
+                (0..N_CORNERS)
+                    .flat_map(|j| (0..3).map(move |o| (j, o)))
+                    .filter(|&(j, o)| self.matches(&position, &CORNER_COLOR[j], o))
+                    .map(|(j, o)| (j, o as u8))
+                    .collect()
+            })
+            .collect();
+        let edge_candidates: Vec<Vec<(usize, u8)>> = (0..N_EDGES)
+            .map(|i| {
+                let position: Vec<usize> = EDGE_FACELET[i].iter().map(|&f| f as usize).collect();
+                (0..N_EDGES)
+                    .flat_map(|j| (0..2).map(move |o| (j, o)))
+                    .filter(|&(j, o)| self.matches(&position, &EDGE_COLOR[j], o))
+                    .map(|(j, o)| (j, o as u8))
+                    .collect()
+            })
+            .collect();
+
+        let centers_ok = (0..N_COLORS).all(|face| {
+            self.facelets[9 * face + 4].is_none_or(|color| color as usize == face)
+        });
+        if !centers_ok {
+            return Vec::new();
+        }
+
+        let mut search = Search {
+            corner_candidates,
+            edge_candidates,
+            corners: [(0, 0); 8],
+            edges: [(0, 0); 12],
+            limit,
+            found: Vec::new(),
+        };
+        search.place_corner(0, 0);
+        search.found
+    }
+
+    /// Enumerate the legal cubes which match the known stickers (at most MAX_COMPLETIONS)
+    pub fn resolve(&self) -> Resolution {
+        let completions = self.completions(MAX_COMPLETIONS);
+        match completions.len() {
+            0 => Resolution::Impossible,
+            1 => Resolution::Unique(completions[0]),
+            n => {
+                // For each sticker which differs, the size of the biggest group of completions with the same color:
+                // the smaller, the better the sticker splits the completions
+                let mut stickers: Vec<(usize, usize)> = (0..54)
+                    .filter_map(|index| {
+                        let mut counts = [0; N_COLORS];
+                        for cube in &completions {
+                            counts[cube.get_facelet(index) as usize] += 1;
+                        }
+                        let biggest = *counts.iter().max().unwrap();
+                        if biggest < n { Some((biggest, index)) } else { None }
+                    })
+                    .collect();
+                stickers.sort();
+                Resolution::Ambiguous {
+                    truncated: n == MAX_COMPLETIONS,
+                    completions,
+                    stickers: stickers.into_iter().map(|(_, index)| index).collect(),
+                }
+            }
+        }
+    }
+}
+
+impl Default for PartialFaceCube {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A complete cube is a partial cube without unknown stickers
+impl From<FaceCube> for PartialFaceCube {
+    fn from(cube: FaceCube) -> Self {
+        let mut facelets = [None; 54];
+        for (index, facelet) in facelets.iter_mut().enumerate() {
+            *facelet = Some(cube.get_facelet(index));
+        }
+        Self { facelets }
+    }
+}
+
+/// Parse a facelet string ("UUUUUUUUURRR...") in the order of the Facelet enum, where '?', '.', '_' or '-' is an
+/// unknown sticker
+impl FromStr for PartialFaceCube {
+    type Err = String;
+
+    fn from_str(str_cube: &str) -> Result<Self, Self::Err> {
+        if str_cube.chars().count() != 54 {
+            return Err(String::from("pas le bon nombre de facelets"));
+        }
+
+        let mut facelets = [None; 54];
+        for (index, color) in str_cube.chars().enumerate() {
+            facelets[index] = match color {
+                'U' => Some(Color::U),
+                'R' => Some(Color::R),
+                'F' => Some(Color::F),
+                'D' => Some(Color::D),
+                'L' => Some(Color::L),
+                'B' => Some(Color::B),
+                '?' | '.' | '_' | '-' => None,
+                _ => {
+                    return Err(format!("pas les bonnes lettres: {}", color));
+                }
+            };
+        }
+        Ok(Self { facelets })
+    }
+}
+
+/// The backtracking search of PartialFaceCube::completions
+struct Search {
+    corner_candidates: Vec<Vec<(usize, u8)>>,
+    edge_candidates: Vec<Vec<(usize, u8)>>,
+    corners: [(usize, u8); 8],
+    edges: [(usize, u8); 12],
+    limit: usize,
+    found: Vec<FaceCube>,
+}
+
+impl Search {
+    /// Place a corner at the position `pos`, `used` is the set (as bits) of the corners already placed
+    fn place_corner(&mut self, pos: usize, used: u32) {
+        if self.found.len() >= self.limit {
+            return;
+        }
+        if pos == N_CORNERS {
+            let twist: usize = self.corners.iter().map(|&(_, o)| o as usize).sum();
+            if twist.is_multiple_of(3) {
+                self.place_edge(0, 0);
+            }
+            return;
+        }
+        for c in 0..self.corner_candidates[pos].len() {
+            let (corner, ori) = self.corner_candidates[pos][c];
+            if used & (1 << corner) == 0 {
+                self.corners[pos] = (corner, ori);
+                self.place_corner(pos + 1, used | (1 << corner));
+            }
+        }
+    }
+
+    /// Place an edge at the position `pos`, `used` is the set (as bits) of the edges already placed
+    fn place_edge(&mut self, pos: usize, used: u32) {
+        if self.found.len() >= self.limit {
+            return;
+        }
+        if pos == N_EDGES {
+            let mut cp = CP_DEFAULT;
+            let mut co = CO_DEFAULT;
+            let mut ep = EP_DEFAULT;
+            let mut eo = EO_DEFAULT;
+            for (i, &(corner, ori)) in self.corners.iter().enumerate() {
+                cp[i] = Corner::from(corner);
+                co[i] = ori;
+            }
+            for (i, &(edge, ori)) in self.edges.iter().enumerate() {
+                ep[i] = Edge::from(edge);
+                eo[i] = ori;
+            }
+            let cube = Cube::new(cp, co, ep, eo);
+            if cube.check().is_ok() {
+                self.found.push(cube.to_facelet_cube());
+            }
+            return;
+        }
+        for e in 0..self.edge_candidates[pos].len() {
+            let (edge, ori) = self.edge_candidates[pos][e];
+            if used & (1 << edge) == 0 {
+                self.edges[pos] = (edge, ori);
+                self.place_edge(pos + 1, used | (1 << edge));
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+// Serde support for the cube states and the moves (feature "serde")
+//
+// * FaceCube is written as its facelet string: "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB"
+// * Cube is written with its explicit form: {"cp": ["URF", ...], "co": [0, ...], "ep": ["UR", ...], "eo": [0, ...]}
+// * Both are read from any of the two forms, and are checked to be valid
+// * Move is written in the standard notation: "R", "R2", "R'"
+// * An algorithm (Vec<Move>) is written as one string: "R U R' U'" (use #[serde(with = "algorithm")])
+
+use super::cube::Cube;
+use super::enums::{ Corner, Edge, Move };
+use super::face_cube::FaceCube;
+use serde::de::Error;
+use serde::{ Deserialize, Deserializer, Serialize, Serializer };
+
+/// The two accepted forms of a cube state
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum CubeRepr {
+    Facelets(String),
+    Cubies {
+        cp: [Corner; 8],
+        co: [u8; 8],
+        ep: [Edge; 12],
+        eo: [u8; 12],
+    },
+}
+
+impl CubeRepr {
+    /// Build the cubie cube described and check that it is valid
+    fn to_cube<E: Error>(&self) -> Result<Cube, E> {
+        let cube = match self {
+            CubeRepr::Facelets(facelets) => {
+                facelets.parse::<FaceCube>().map_err(E::custom)?.to_cubie_cube()
+            }
+            CubeRepr::Cubies { cp, co, ep, eo } => Cube::new(*cp, *co, *ep, *eo),
+        };
+        cube.check().map_err(E::custom)?;
+        Ok(cube)
+    }
+}
+
+impl Serialize for Cube {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        CubeRepr::Cubies {
+            cp: self.get_cp(),
+            co: self.get_co(),
+            ep: self.get_ep(),
+            eo: self.get_eo(),
+        }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Cube {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        CubeRepr::deserialize(deserializer)?.to_cube()
+    }
+}
+
+impl Serialize for FaceCube {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for FaceCube {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(CubeRepr::deserialize(deserializer)?.to_cube::<D::Error>()?.to_facelet_cube())
+    }
+}
+
+impl Serialize for Move {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if *self == Move::Default {
+            return Err(serde::ser::Error::custom("Move::Default has no notation"));
+        }
+        serializer.serialize_str(&self.to_notation())
+    }
+}
+
+impl<'de> Deserialize<'de> for Move {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+    }
+}
+
+/// Serialize a Vec<Move> as one notation string, to use with #[serde(with = "...")]
+pub mod algorithm {
+    use super::super::enums::Move;
+    use super::super::notation::{ algorithm_to_string, parse_algorithm };
+    use serde::de::Error;
+    use serde::{ Deserialize, Deserializer, Serializer };
+
+    pub fn serialize<S: Serializer>(moves: &[Move], serializer: S) -> Result<S::Ok, S::Error> {
+        if moves.contains(&Move::Default) {
+            return Err(serde::ser::Error::custom("Move::Default has no notation"));
+        }
+        serializer.serialize_str(&algorithm_to_string(moves))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Move>, D::Error> {
+        parse_algorithm(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
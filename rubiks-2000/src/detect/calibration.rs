@@ -0,0 +1,225 @@
+// Color calibration under a given lighting.
+//
+// The user scans a solved cube. The white face gives a white balance (a gain for each channel, so that it becomes
+// neutral), then the stickers of each face give the center of its color cluster in the CIE Lab space, where the
+// distances are close to the perceived differences (red/orange and white/yellow are better separated than in RGB).
+//
+// A profile is saved as a small text file:
+//
+// # rubiks-2000 calibration profile
+// white_balance 1.0000 1.0420 1.3070
+// U 97.120 -0.410 1.930 4.000
+// R 41.800 60.530 35.170 4.520
+// ...
+//
+// one line per face color: L, a, b of the center of the cluster and its spread.
+
+use super::classify::{ classify_with_references, distance2, Detection };
+use super::sample::Rgb;
+use crate::cube::defs::N_COLORS;
+use crate::cube::enums::Color;
+use crate::cube::formats::FACE_LETTERS;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// The smallest spread of a color cluster, in Lab units (a perfect scan would give a null spread)
+const MIN_SPREAD: f32 = 4.0;
+
+/// The header of the profile files
+const PROFILE_HEADER: &str = "# rubiks-2000 calibration profile";
+
+/// A color in the CIE Lab space (D65 white)
+pub type Lab = [f32; 3];
+
+/// The errors of the calibration and of the profile files
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalibrationError {
+    /// The scanned cube is not solved: a sticker is closer to the color of another face
+    NotSolved(Color),
+    /// The profile file can not be read or written
+    Io(String),
+    /// The profile file is malformed
+    Parse(String),
+}
+
+impl fmt::Display for CalibrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalibrationError::NotSolved(face) => write!(f, "The face {} of the calibration cube is not solved", face),
+            CalibrationError::Io(message) => write!(f, "Can not access the profile: {}", message),
+            CalibrationError::Parse(message) => write!(f, "Bad profile: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for CalibrationError {}
+
+/// Convert an RGB color (sRGB, components between 0 and 255) to Lab
+pub fn rgb_to_lab(rgb: &Rgb) -> Lab {
+    let linear = rgb.map(|c| {
+        let c = c.max(0.0) / 255.0;
+        if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+    });
+    let [r, g, b] = linear;
+    let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+    let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+    let f = |t: f32| if t > 0.008856 { t.cbrt() } else { 7.787 * t + 16.0 / 116.0 };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+/// The calibration of the colors under a given lighting
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationProfile {
+    /// The gain of each channel (R, G, B) applied before the conversion to Lab
+    pub white_balance: [f32; 3],
+    /// The center of the cluster of each color in Lab (indexed by the Color enum)
+    pub centers: [Lab; N_COLORS],
+    /// The spread of each cluster (root mean square distance to its center, in Lab units)
+    pub spreads: [f32; N_COLORS],
+}
+
+impl CalibrationProfile {
+    /// Compute a profile from the scan of a solved cube
+    ///
+    /// # Args
+    /// * 'colors' - the sampled color of each sticker, in the order of the Facelet enum
+    pub fn calibrate(colors: &[Rgb; 54]) -> Result<Self, CalibrationError> {
+        let mean = |face: usize| {
+            let mut sum = [0.0; 3];
+            for color in &colors[9 * face..9 * face + 9] {
+                for (s, c) in sum.iter_mut().zip(color) {
+                    *s += c / 9.0;
+                }
+            }
+            sum
+        };
+
+        // The white face is the brightest one in its darkest channel
+        let darkest = |rgb: Rgb| rgb.iter().copied().fold(f32::INFINITY, f32::min);
+        let white = (0..N_COLORS).map(mean).max_by(|a, b| darkest(*a).total_cmp(&darkest(*b))).unwrap();
+        let brightest = white.iter().copied().fold(0.0, f32::max);
+        let white_balance = white.map(|c| brightest / c.max(1.0));
+
+        let mut profile = Self { white_balance, centers: [[0.0; 3]; N_COLORS], spreads: [MIN_SPREAD; N_COLORS] };
+        for face in 0..N_COLORS {
+            let labs: Vec<Lab> = colors[9 * face..9 * face + 9].iter().map(|rgb| profile.to_lab(rgb)).collect();
+            let mut center = [0.0; 3];
+            for lab in &labs {
+                for (c, l) in center.iter_mut().zip(lab) {
+                    *c += l / 9.0;
+                }
+            }
+            let variance: f32 = labs.iter().map(|lab| distance2(lab, &center)).sum::<f32>() / 9.0;
+            profile.centers[face] = center;
+            profile.spreads[face] = variance.sqrt().max(MIN_SPREAD);
+        }
+
+        // Each sticker must be closer to the center of its face than to the other ones
+        for face in 0..N_COLORS {
+            if colors[9 * face..9 * face + 9].iter().any(|rgb| profile.nearest(rgb) != Color::from(face)) {
+                return Err(CalibrationError::NotSolved(Color::from(face)));
+            }
+        }
+        Ok(profile)
+    }
+
+    /// Convert a sampled color to Lab, after the white balance
+    pub fn to_lab(&self, rgb: &Rgb) -> Lab {
+        rgb_to_lab(&[0, 1, 2].map(|c| rgb[c] * self.white_balance[c]))
+    }
+
+    /// Return the color whose cluster is the nearest to a sampled color
+    pub fn nearest(&self, rgb: &Rgb) -> Color {
+        let lab = self.to_lab(rgb);
+        let distance = |face: &usize| distance2(&lab, &self.centers[*face]);
+        Color::from((0..N_COLORS).min_by(|a, b| distance(a).total_cmp(&distance(b))).unwrap())
+    }
+
+    /// Classify the stickers of a face as soon as it is captured (see scan::ScanSession)
+    pub fn classify_face(&self, colors: &[Rgb; 9]) -> [Color; 9] {
+        colors.map(|rgb| self.nearest(&rgb))
+    }
+
+    /// Classify the stickers of the six faces with the profile instead of the colors of the centers
+    ///
+    /// # Args
+    /// * 'colors' - the sampled color of each sticker, in the order of the Facelet enum
+    pub fn classify(&self, colors: &[Rgb; 54]) -> Detection {
+        let labs = colors.map(|rgb| self.to_lab(&rgb));
+        let sigma = self.spreads.iter().sum::<f32>() / N_COLORS as f32;
+        let mut detection = classify_with_references(&labs, &self.centers, sigma);
+        detection.colors = *colors;
+        detection
+    }
+
+    /// Read a profile file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, CalibrationError> {
+        std::fs::read_to_string(path).map_err(|error| CalibrationError::Io(error.to_string()))?.parse()
+    }
+
+    /// Write the profile to a file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), CalibrationError> {
+        std::fs::write(path, self.to_string()).map_err(|error| CalibrationError::Io(error.to_string()))
+    }
+}
+
+impl fmt::Display for CalibrationProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", PROFILE_HEADER)?;
+        let [r, g, b] = self.white_balance;
+        writeln!(f, "white_balance {:.4} {:.4} {:.4}", r, g, b)?;
+        for (face, letter) in FACE_LETTERS.iter().enumerate() {
+            let [l, a, b] = self.centers[face];
+            writeln!(f, "{} {:.3} {:.3} {:.3} {:.3}", letter, l, a, b, self.spreads[face])?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse a profile file (see the header of this file). The empty lines and the comments (#) are ignored.
+impl FromStr for CalibrationProfile {
+    type Err = CalibrationError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut white_balance = None;
+        let mut centers = [None; N_COLORS];
+        let mut spreads = [MIN_SPREAD; N_COLORS];
+
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            let mut words = line.split_whitespace();
+            let key = words.next().unwrap();
+            let values = words
+                .map(|word| word.parse::<f32>())
+                .collect::<Result<Vec<f32>, _>>()
+                .map_err(|_| CalibrationError::Parse(format!("bad number in \"{}\"", line)))?;
+
+            if key == "white_balance" {
+                if values.len() != 3 {
+                    return Err(CalibrationError::Parse(String::from("white_balance needs 3 gains")));
+                }
+                white_balance = Some([values[0], values[1], values[2]]);
+                continue;
+            }
+            let face = FACE_LETTERS
+                .iter()
+                .position(|letter| key.len() == 1 && key.starts_with(*letter))
+                .ok_or_else(|| CalibrationError::Parse(format!("unknown key {}", key)))?;
+            if values.len() != 4 {
+                return Err(CalibrationError::Parse(format!("{} needs L, a, b and the spread", key)));
+            }
+            centers[face] = Some([values[0], values[1], values[2]]);
+            spreads[face] = values[3].max(MIN_SPREAD);
+        }
+
+        let white_balance = white_balance.ok_or_else(|| CalibrationError::Parse(String::from("no white_balance")))?;
+        let mut found = [[0.0; 3]; N_COLORS];
+        for face in 0..N_COLORS {
+            found[face] = centers[face]
+                .ok_or_else(|| CalibrationError::Parse(format!("no color for the face {}", FACE_LETTERS[face])))?;
+        }
+        Ok(Self { white_balance, centers: found, spreads })
+    }
+}
@@ -0,0 +1,188 @@
+// Detection of the state of a cube from six pictures, one per face.
+//
+// Each picture must show the face in the orientation of the net drawn in the Facelet enum doc comment:
+// the U face with the B face above it, the D face with the F face above it, and the side faces (R, F, L, B)
+// with the U face above them.
+//
+// The stickers are sampled (see sample.rs) and compared to the six centers: the center of each face gives the
+// color of this face. The probability of each color is computed from the distance to the centers, so that a
+// wrong reading can be fixed by cube::correction::nearest_legal_state.
+
+use super::calibration::CalibrationProfile;
+use super::grid::{ locate_grid, GridRejection };
+use super::sample::{ sample_stickers, GridLocation, Rgb };
+use crate::cube::correction::{ nearest_legal_state, ColorProbabilities, Correction };
+use crate::cube::defs::N_COLORS;
+use crate::cube::enums::Color;
+use crate::cube::face_cube::FaceCube;
+use image::RgbImage;
+use std::fmt;
+use std::path::Path;
+
+/// The spread of the colors of the stickers around the color of their center (in RGB units):
+/// a sticker at this distance of a center is exp(-1/2) times less likely to be of its color
+const COLOR_SIGMA: f32 = 30.0;
+
+/// The reasons why a detection can fail
+#[derive(Debug, Clone, PartialEq)]
+pub enum DetectError {
+    /// A picture can not be read (the message of the image library)
+    Image(String),
+    /// The sticker grid of a face is not inside its picture
+    GridOutsideImage(Color),
+    /// No sticker grid was found in a picture
+    GridNotFound(GridRejection),
+}
+
+impl fmt::Display for DetectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DetectError::Image(message) => write!(f, "Can not read the picture: {}", message),
+            DetectError::GridOutsideImage(face) => write!(f, "The sticker grid of the face {} is outside the picture", face),
+            DetectError::GridNotFound(rejection) => write!(f, "No sticker grid found: {}", rejection),
+        }
+    }
+}
+
+impl std::error::Error for DetectError {}
+
+/// The picture of a face and the location of its sticker grid
+#[derive(Debug, Clone)]
+pub struct FacePicture {
+    pub image: RgbImage,
+    pub grid: GridLocation,
+}
+
+impl FacePicture {
+    /// Read the picture of a face from a file (PNG or JPEG)
+    ///
+    /// # Args
+    /// * 'path' - the path of the picture
+    /// * 'grid' - the location of the sticker grid in the picture
+    pub fn open<P: AsRef<Path>>(path: P, grid: GridLocation) -> Result<Self, DetectError> {
+        let image = image::open(path).map_err(|error| DetectError::Image(error.to_string()))?;
+        Ok(Self { image: image.to_rgb8(), grid })
+    }
+
+    /// Create the picture of a face, its sticker grid being found automatically (see grid::locate_grid)
+    pub fn locate(image: RgbImage) -> Result<Self, DetectError> {
+        let grid = locate_grid(&image).map_err(DetectError::GridNotFound)?.grid;
+        Ok(Self { image, grid })
+    }
+
+    /// Read the picture of a face from a file (PNG or JPEG), its sticker grid being found automatically
+    pub fn open_located<P: AsRef<Path>>(path: P) -> Result<Self, DetectError> {
+        let image = image::open(path).map_err(|error| DetectError::Image(error.to_string()))?;
+        Self::locate(image.to_rgb8())
+    }
+}
+
+/// The result of a detection
+#[derive(Debug, Clone)]
+pub struct Detection {
+    /// The most likely color of each sticker. Nothing ensures that it is a legal cube.
+    pub cube: FaceCube,
+    /// The probability of the color of each sticker in `cube`
+    pub confidence: [f32; 54],
+    /// The probability of each color for each sticker
+    pub probabilities: ColorProbabilities,
+    /// The sampled color of each sticker
+    pub colors: [Rgb; 54],
+}
+
+impl Detection {
+    /// Return the most likely legal cube, see cube::correction::nearest_legal_state
+    pub fn correct(&self) -> Correction {
+        nearest_legal_state(&self.probabilities)
+    }
+
+    /// Return the stickers whose confidence is lower than `threshold`
+    pub fn uncertain_stickers(&self, threshold: f32) -> Vec<usize> {
+        (0..54).filter(|&index| self.confidence[index] < threshold).collect()
+    }
+}
+
+/// Return the square of the distance between two colors
+pub fn distance2(a: &Rgb, b: &Rgb) -> f32 {
+    (0..3).map(|c| (a[c] - b[c]).powi(2)).sum()
+}
+
+/// Classify the stickers against reference colors
+///
+/// # Args
+/// * 'colors' - the color of each sticker, in the order of the Facelet enum
+/// * 'references' - the color of each face, indexed by the Color enum
+/// * 'sigma' - the spread of the sticker colors around their reference color
+pub fn classify_with_references(colors: &[Rgb; 54], references: &[Rgb; N_COLORS], sigma: f32) -> Detection {
+    let mut cube = FaceCube::new();
+    let mut confidence = [0.0; 54];
+    let mut probabilities = [[0.0; N_COLORS]; 54];
+
+    for index in 0..54 {
+        // exp(-d^2 / (2 sigma^2)), shifted by the smallest distance to avoid underflows
+        let distances: Vec<f32> = references.iter().map(|reference| distance2(&colors[index], reference)).collect();
+        let min = distances.iter().copied().fold(f32::INFINITY, f32::min);
+        let weights: Vec<f32> = distances.iter().map(|d| (-(d - min) / (2.0 * sigma * sigma)).exp()).collect();
+        let total: f32 = weights.iter().sum();
+
+        let mut best = 0;
+        for color in 0..N_COLORS {
+            probabilities[index][color] = weights[color] / total;
+            if weights[color] > weights[best] {
+                best = color;
+            }
+        }
+        cube.set_facelet(index, Color::from(best));
+        confidence[index] = probabilities[index][best];
+    }
+
+    Detection { cube, confidence, probabilities, colors: *colors }
+}
+
+/// Classify the stickers against the colors of the six centers
+///
+/// # Args
+/// * 'colors' - the color of each sticker, in the order of the Facelet enum
+pub fn classify(colors: &[Rgb; 54]) -> Detection {
+    let mut references = [[0.0; 3]; N_COLORS];
+    for (face, reference) in references.iter_mut().enumerate() {
+        *reference = colors[9 * face + 4];
+    }
+    classify_with_references(colors, &references, COLOR_SIGMA)
+}
+
+/// Sample the stickers of the six faces, in the order of the Facelet enum
+///
+/// # Args
+/// * 'pictures' - the pictures of the faces, indexed by the Color enum
+pub fn sample_faces(pictures: &[FacePicture; N_COLORS]) -> Result<[Rgb; 54], DetectError> {
+    let mut colors = [[0.0; 3]; 54];
+    for (face, picture) in pictures.iter().enumerate() {
+        if !picture.grid.is_inside(picture.image.width(), picture.image.height()) {
+            return Err(DetectError::GridOutsideImage(Color::from(face)));
+        }
+        let stickers = sample_stickers(&picture.image, &picture.grid);
+        colors[9 * face..9 * face + 9].copy_from_slice(&stickers);
+    }
+    Ok(colors)
+}
+
+/// Detect the state of the cube from the pictures of its six faces
+///
+/// # Args
+/// * 'pictures' - the pictures of the faces, indexed by the Color enum
+pub fn detect(pictures: &[FacePicture; N_COLORS]) -> Result<Detection, DetectError> {
+    Ok(classify(&sample_faces(pictures)?))
+}
+
+/// Detect the state of the cube from the pictures of its six faces, with the colors of a calibration profile
+///
+/// # Args
+/// * 'pictures' - the pictures of the faces, indexed by the Color enum
+/// * 'profile' - the calibration of the colors under the current lighting
+pub fn detect_with_profile(
+    pictures: &[FacePicture; N_COLORS],
+    profile: &CalibrationProfile
+) -> Result<Detection, DetectError> {
+    Ok(profile.classify(&sample_faces(pictures)?))
+}
@@ -0,0 +1,223 @@
+// Sources of timestamped RGB frames for the live detection.
+//
+// The camera source reads a V4L2 device with rscam (feature "camera", Linux only). The directory and raw dump
+// sources replay recorded frames, so that the detection loop can be developed and tested without a webcam.
+// A raw dump is a sequence of RGB frames of the same size, 3 bytes per pixel, row by row, without header
+// (see write_raw_frame).
+
+use image::RgbImage;
+use std::fmt;
+use std::fs::File;
+use std::io::{ BufReader, ErrorKind, Read, Write };
+use std::path::{ Path, PathBuf };
+use std::time::Duration;
+
+/// The extensions of the pictures replayed by DirectorySource
+const IMAGE_EXTENSIONS: [&str; 3] = ["png", "jpg", "jpeg"];
+
+/// A frame of a source
+#[derive(Debug, Clone)]
+pub struct Frame {
+    /// The time of the frame since the first frame of the source
+    pub timestamp: Duration,
+    pub image: RgbImage,
+}
+
+/// The reasons why a frame can not be read
+#[derive(Debug, Clone, PartialEq)]
+pub enum FrameError {
+    /// The device or the file can not be read
+    Io(String),
+    /// A picture can not be decoded
+    Image(String),
+    /// The frame has not the expected size or format
+    Format(String),
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::Io(message) => write!(f, "Can not read the frame: {}", message),
+            FrameError::Image(message) => write!(f, "Can not decode the frame: {}", message),
+            FrameError::Format(message) => write!(f, "Bad frame format: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+impl From<std::io::Error> for FrameError {
+    fn from(error: std::io::Error) -> Self {
+        FrameError::Io(error.to_string())
+    }
+}
+
+/// A source of frames (camera, recording...)
+pub trait FrameSource {
+    /// Return the next frame, None at the end of the source (a camera never ends)
+    fn next_frame(&mut self) -> Result<Option<Frame>, FrameError>;
+}
+
+/// Replay the pictures of a directory (PNG or JPEG), in the order of their names
+pub struct DirectorySource {
+    paths: Vec<PathBuf>,
+    next: usize,
+    interval: Duration,
+}
+
+impl DirectorySource {
+    /// Create a source from the pictures of a directory
+    ///
+    /// # Args
+    /// * 'directory' - the directory of the pictures, the other files are ignored
+    /// * 'interval' - the time between two frames
+    pub fn new<P: AsRef<Path>>(directory: P, interval: Duration) -> Result<Self, FrameError> {
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(directory)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .and_then(|extension| extension.to_str())
+                    .is_some_and(|extension| IMAGE_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+            })
+            .collect();
+        paths.sort();
+        Ok(Self { paths, next: 0, interval })
+    }
+
+    /// Return the number of frames of the source
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Return true if the directory has no picture
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+}
+
+impl FrameSource for DirectorySource {
+    fn next_frame(&mut self) -> Result<Option<Frame>, FrameError> {
+        let Some(path) = self.paths.get(self.next) else {
+            return Ok(None);
+        };
+        let image = image::open(path).map_err(|error| FrameError::Image(format!("{}: {}", path.display(), error)))?;
+        let timestamp = self.interval * self.next as u32;
+        self.next += 1;
+        Ok(Some(Frame { timestamp, image: image.to_rgb8() }))
+    }
+}
+
+/// Replay a raw dump of RGB frames (see write_raw_frame)
+pub struct RawSource<R: Read> {
+    reader: R,
+    width: u32,
+    height: u32,
+    next: u32,
+    interval: Duration,
+}
+
+impl<R: Read> RawSource<R> {
+    /// Create a source from a reader of raw frames
+    ///
+    /// # Args
+    /// * 'reader' - the raw frames
+    /// * 'width, height' - the size of the frames, in pixels
+    /// * 'interval' - the time between two frames
+    pub fn new(reader: R, width: u32, height: u32, interval: Duration) -> Self {
+        Self { reader, width, height, next: 0, interval }
+    }
+}
+
+impl RawSource<BufReader<File>> {
+    /// Create a source from a raw dump file
+    pub fn open<P: AsRef<Path>>(path: P, width: u32, height: u32, interval: Duration) -> Result<Self, FrameError> {
+        Ok(Self::new(BufReader::new(File::open(path)?), width, height, interval))
+    }
+}
+
+impl<R: Read> FrameSource for RawSource<R> {
+    fn next_frame(&mut self) -> Result<Option<Frame>, FrameError> {
+        let mut buffer = vec![0; 3 * self.width as usize * self.height as usize];
+        let mut read = 0;
+        while read < buffer.len() {
+            match self.reader.read(&mut buffer[read..]) {
+                Ok(0) => break,
+                Ok(n) => read += n,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error.into()),
+            }
+        }
+        if read == 0 {
+            return Ok(None);
+        }
+        if read < buffer.len() {
+            return Err(FrameError::Format(format!("truncated frame ({} bytes of {})", read, buffer.len())));
+        }
+
+        let timestamp = self.interval * self.next;
+        self.next += 1;
+        let image = RgbImage::from_raw(self.width, self.height, buffer).unwrap();
+        Ok(Some(Frame { timestamp, image }))
+    }
+}
+
+/// Append a frame to a raw dump, to record the frames of a source and replay them with RawSource
+pub fn write_raw_frame<W: Write>(writer: &mut W, image: &RgbImage) -> Result<(), FrameError> {
+    writer.write_all(image.as_raw())?;
+    Ok(())
+}
+
+/// The frames of a V4L2 camera
+#[cfg(feature = "camera")]
+pub struct CameraSource {
+    camera: rscam::Camera,
+    /// The timestamp of the first frame, in microseconds
+    start: Option<u64>,
+}
+
+#[cfg(feature = "camera")]
+impl CameraSource {
+    /// Open and start a camera, the frames are read in YUYV (supported by almost all the webcams)
+    ///
+    /// # Args
+    /// * 'device' - the path of the device, such as "/dev/video0"
+    /// * 'width, height' - the size of the frames, in pixels
+    /// * 'fps' - the number of frames per second
+    pub fn new(device: &str, width: u32, height: u32, fps: u32) -> Result<Self, FrameError> {
+        let mut camera = rscam::new(device)?;
+        camera
+            .start(&rscam::Config { interval: (1, fps), resolution: (width, height), format: b"YUYV", ..Default::default() })
+            .map_err(|error| FrameError::Io(error.to_string()))?;
+        Ok(Self { camera, start: None })
+    }
+}
+
+/// Convert a YUV color (BT.601) to RGB
+#[cfg(feature = "camera")]
+fn yuv_to_rgb(y: u8, u: u8, v: u8) -> [u8; 3] {
+    let (y, u, v) = (y as f32, u as f32 - 128.0, v as f32 - 128.0);
+    [y + 1.402 * v, y - 0.344 * u - 0.714 * v, y + 1.772 * u].map(|c| c.clamp(0.0, 255.0) as u8)
+}
+
+#[cfg(feature = "camera")]
+impl FrameSource for CameraSource {
+    fn next_frame(&mut self) -> Result<Option<Frame>, FrameError> {
+        let frame = self.camera.capture()?;
+        let (width, height) = frame.resolution;
+        if &frame.format != b"YUYV" || frame.len() < 2 * width as usize * height as usize {
+            return Err(FrameError::Format(format!("unexpected {} frame", String::from_utf8_lossy(&frame.format))));
+        }
+
+        // Two pixels are coded with 4 bytes: Y0 U Y1 V
+        let mut rgb = Vec::with_capacity(3 * width as usize * height as usize);
+        for chunk in frame[..2 * width as usize * height as usize].chunks_exact(4) {
+            rgb.extend(yuv_to_rgb(chunk[0], chunk[1], chunk[3]));
+            rgb.extend(yuv_to_rgb(chunk[2], chunk[1], chunk[3]));
+        }
+
+        let start = *self.start.get_or_insert(frame.get_timestamp());
+        let timestamp = Duration::from_micros(frame.get_timestamp().saturating_sub(start));
+        let image = RgbImage::from_raw(width, height, rgb).unwrap();
+        Ok(Some(Frame { timestamp, image }))
+    }
+}
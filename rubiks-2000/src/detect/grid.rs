@@ -0,0 +1,369 @@
+// Automatic localization of the 3x3 sticker grid of a face in a picture, so that the face can be anywhere in the
+// picture (no fixed overlay box).
+//
+// 1. Edge detection: Sobel gradient of the blurred gray levels, thresholded.
+// 2. Square contours: the connected regions between the edges are the candidate stickers. The quadrilateral of a
+//    region is found from its farthest points, and the region is kept if it fills its quadrilateral, with sides
+//    of similar lengths.
+// 3. Grid: each square is tried as the center sticker. The other squares are expressed in the axes of its sides,
+//    and those which fall on the 3x3 lattice around it are the other stickers.
+// 4. Perspective fit: a homography from the grid coordinates to the picture is fitted (least squares) on the
+//    centers of the stickers found, and gives the nine sticker quadrilaterals.
+
+use super::sample::GridLocation;
+use image::RgbImage;
+use std::fmt;
+
+/// The gradient above which a pixel is an edge (gray levels between 0 and 255)
+const EDGE_THRESHOLD: f32 = 25.0;
+
+/// The smallest area of a sticker, in pixels
+const MIN_STICKER_AREA: usize = 30;
+
+/// The smallest number of stickers found to accept a grid (the other ones are given by the perspective fit)
+pub const MIN_STICKERS_FOUND: usize = 7;
+
+/// The largest distance of a sticker to its lattice position, relatively to the pitch of the grid
+const LATTICE_TOLERANCE: f32 = 0.25;
+
+/// A point in pixels (x, y)
+pub type Point = (f32, f32);
+
+/// The reasons why no grid is found in a picture
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridRejection {
+    /// The picture is too small to contain a grid
+    TooSmall,
+    /// No region of the picture looks like a sticker
+    NoSquares,
+    /// Some stickers were found, but at most `best` of them on a 3x3 grid
+    NoGrid { squares: usize, best: usize },
+    /// The stickers found do not fit a perspective view of a grid
+    BadFit,
+}
+
+impl fmt::Display for GridRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridRejection::TooSmall => write!(f, "The picture is too small"),
+            GridRejection::NoSquares => write!(f, "No sticker found in the picture"),
+            GridRejection::NoGrid { squares, best } => write!(
+                f,
+                "{} stickers found, but only {} of them on a grid ({} needed)",
+                squares, best, MIN_STICKERS_FOUND
+            ),
+            GridRejection::BadFit => write!(f, "The stickers found do not form a face"),
+        }
+    }
+}
+
+/// A sticker grid found in a picture
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridDetection {
+    /// The location of the whole grid
+    pub grid: GridLocation,
+    /// The quadrilateral of each sticker (row by row), corners in the order of GridLocation
+    pub stickers: [[Point; 4]; 9],
+    /// True for the stickers found in the picture, false for the ones given by the perspective fit only
+    pub found: [bool; 9],
+}
+
+/// A candidate sticker
+#[derive(Debug, Clone, Copy)]
+struct Square {
+    center: Point,
+    /// The axes of the square (a side vector for the columns and one for the rows)
+    u: Point,
+    v: Point,
+    area: f32,
+}
+
+/// A perspective transformation from the grid coordinates ((0, 0) top left, (1, 1) bottom right) to the picture
+struct Homography {
+    h: [f64; 8],
+}
+
+impl Homography {
+    /// Fit the homography on pairs (grid point, picture point) with least squares, None if it is degenerate
+    fn fit(pairs: &[(Point, Point)]) -> Option<Self> {
+        // x = (h0 X + h1 Y + h2) / (h6 X + h7 Y + 1) and y = (h3 X + h4 Y + h5) / (h6 X + h7 Y + 1)
+        let mut ata = [[0.0f64; 8]; 8];
+        let mut atb = [0.0f64; 8];
+        for &((gx, gy), (x, y)) in pairs {
+            let (gx, gy, x, y) = (gx as f64, gy as f64, x as f64, y as f64);
+            let rows = [
+                ([gx, gy, 1.0, 0.0, 0.0, 0.0, -gx * x, -gy * x], x),
+                ([0.0, 0.0, 0.0, gx, gy, 1.0, -gx * y, -gy * y], y),
+            ];
+            for (row, b) in rows {
+                for i in 0..8 {
+                    for j in 0..8 {
+                        ata[i][j] += row[i] * row[j];
+                    }
+                    atb[i] += row[i] * b;
+                }
+            }
+        }
+        solve(ata, atb).map(|h| Self { h })
+    }
+
+    /// Return the picture point of the grid point (gx, gy)
+    fn map(&self, gx: f32, gy: f32) -> Point {
+        let h = &self.h;
+        let (gx, gy) = (gx as f64, gy as f64);
+        let w = h[6] * gx + h[7] * gy + 1.0;
+        (((h[0] * gx + h[1] * gy + h[2]) / w) as f32, ((h[3] * gx + h[4] * gy + h[5]) / w) as f32)
+    }
+}
+
+/// Solve the linear system a x = b with the Gauss elimination, None if it is singular
+fn solve(mut a: [[f64; 8]; 8], mut b: [f64; 8]) -> Option<[f64; 8]> {
+    for col in 0..8 {
+        let pivot = (col..8).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-9 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in col + 1..8 {
+            let factor = a[row][col] / a[col][col];
+            let pivot_row = a[col];
+            for (value, pivot_value) in a[row].iter_mut().zip(pivot_row).skip(col) {
+                *value -= factor * pivot_value;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    let mut x = [0.0; 8];
+    for row in (0..8).rev() {
+        let sum: f64 = (row + 1..8).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+fn sub(a: Point, b: Point) -> Point {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+fn norm(a: Point) -> f32 {
+    (a.0 * a.0 + a.1 * a.1).sqrt()
+}
+
+/// The cross product of b - a and c - a (twice the signed area of the triangle)
+fn cross(a: Point, b: Point, c: Point) -> f32 {
+    let (ab, ac) = (sub(b, a), sub(c, a));
+    ab.0 * ac.1 - ab.1 * ac.0
+}
+
+/// Return the edge map of the picture (true for the edge pixels), in the order of the pixels (row by row).
+/// The gradient is computed on each channel: the gray levels of a dark blue sticker are too close to the black
+/// body of the cube.
+fn edges(image: &RgbImage) -> Vec<bool> {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let mut edge = vec![true; width * height];
+
+    for channel in 0..3 {
+        let values: Vec<f32> = image.pixels().map(|p| p[channel] as f32).collect();
+
+        // 3x3 box blur, to remove the noise of the sensor
+        let mut blurred = values.clone();
+        for y in 1..height - 1 {
+            for x in 1..width - 1 {
+                let mut sum = 0.0;
+                for dy in 0..3 {
+                    for dx in 0..3 {
+                        sum += values[(y + dy - 1) * width + x + dx - 1];
+                    }
+                }
+                blurred[y * width + x] = sum / 9.0;
+            }
+        }
+
+        // Sobel gradient, normalized so that a step of n levels gives a gradient of about n
+        for y in 1..height - 1 {
+            for x in 1..width - 1 {
+                let p = |dx: usize, dy: usize| blurred[(y + dy - 1) * width + x + dx - 1];
+                let gx = p(2, 0) + 2.0 * p(2, 1) + p(2, 2) - p(0, 0) - 2.0 * p(0, 1) - p(0, 2);
+                let gy = p(0, 2) + 2.0 * p(1, 2) + p(2, 2) - p(0, 0) - 2.0 * p(1, 0) - p(2, 0);
+                let is_edge = (gx.abs() + gy.abs()) / 4.0 > EDGE_THRESHOLD;
+                if channel == 0 {
+                    edge[y * width + x] = is_edge;
+                } else {
+                    edge[y * width + x] |= is_edge;
+                }
+            }
+        }
+    }
+    edge
+}
+
+/// Return the square which fits the region `pixels`, None if the region is not a square
+fn fit_square(pixels: &[(usize, usize)]) -> Option<Square> {
+    let points: Vec<Point> = pixels.iter().map(|&(x, y)| (x as f32, y as f32)).collect();
+    let n = points.len() as f32;
+    let center = (points.iter().map(|p| p.0).sum::<f32>() / n, points.iter().map(|p| p.1).sum::<f32>() / n);
+
+    // The farthest points give a diagonal, and the farthest points on each side of it the other diagonal
+    let farthest = |from: Point| *points.iter().max_by(|a, b| norm(sub(**a, from)).total_cmp(&norm(sub(**b, from)))).unwrap();
+    let p1 = farthest(center);
+    let p2 = farthest(p1);
+    let p3 = *points.iter().max_by(|a, b| cross(p1, p2, **a).total_cmp(&cross(p1, p2, **b))).unwrap();
+    let p4 = *points.iter().min_by(|a, b| cross(p1, p2, **a).total_cmp(&cross(p1, p2, **b))).unwrap();
+    let corners = [p1, p3, p2, p4];
+
+    // Shoelace formula, +1 pixel on each side because the pixels are counted up to the border
+    let area = (cross(p1, p3, p2) + cross(p1, p2, p4)).abs() / 2.0;
+    let sides: Vec<f32> = (0..4).map(|i| norm(sub(corners[(i + 1) % 4], corners[i]))).collect();
+    let perimeter: f32 = sides.iter().sum();
+    let fill = n / (area + perimeter / 2.0 + 1.0);
+    let longest = sides.iter().copied().fold(0.0, f32::max);
+    let shortest = sides.iter().copied().fold(f32::INFINITY, f32::min);
+    if !(0.85..=1.15).contains(&fill) || shortest < 1.0 || longest / shortest > 1.6 {
+        return None;
+    }
+
+    // The side closest to the horizontal gives the columns, oriented to the right, and the other side the rows,
+    // oriented to the bottom
+    let half = |a: Point, b: Point| ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+    let mut u = half(sub(corners[1], corners[0]), sub(corners[2], corners[3]));
+    let mut v = half(sub(corners[3], corners[0]), sub(corners[2], corners[1]));
+    if v.0.abs() > u.0.abs() {
+        std::mem::swap(&mut u, &mut v);
+    }
+    if u.0 < 0.0 {
+        u = (-u.0, -u.1);
+    }
+    if v.1 < 0.0 {
+        v = (-v.0, -v.1);
+    }
+    Some(Square { center, u, v, area: n })
+}
+
+/// Return the candidate stickers of the picture
+fn find_squares(image: &RgbImage) -> Vec<Square> {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let edge = edges(image);
+    let max_area = width * height / 9;
+
+    // The connected regions (4-neighbors) of non edge pixels
+    let mut visited = edge.clone();
+    let mut squares = Vec::new();
+    for start in 0..width * height {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut stack = vec![start];
+        let mut pixels = Vec::new();
+        let mut on_border = false;
+        while let Some(index) = stack.pop() {
+            let (x, y) = (index % width, index / width);
+            pixels.push((x, y));
+            if x == 0 || y == 0 || x == width - 1 || y == height - 1 {
+                on_border = true;
+                continue;
+            }
+            for next in [index - 1, index + 1, index - width, index + width] {
+                if !visited[next] {
+                    visited[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        if !on_border && (MIN_STICKER_AREA..=max_area).contains(&pixels.len()) {
+            squares.extend(fit_square(&pixels));
+        }
+    }
+    squares
+}
+
+/// Return the stickers around `center` on its 3x3 lattice (row by row, the index of the square or None)
+/// and the number of stickers found
+fn lattice(center: &Square, squares: &[Square]) -> ([Option<usize>; 9], usize) {
+    let det = center.u.0 * center.v.1 - center.u.1 * center.v.0;
+    let similar = |square: &Square| (0.5..=2.0).contains(&(square.area / center.area));
+    // The coordinates of a square in the axes of the center
+    let coordinates = |square: &Square| {
+        let d = sub(square.center, center.center);
+        ((d.0 * center.v.1 - d.1 * center.v.0) / det, (center.u.0 * d.1 - center.u.1 * d.0) / det)
+    };
+
+    // The pitch of the lattice (sticker + gap) is given by the closest neighbor
+    let pitch = squares
+        .iter()
+        .filter(|square| similar(square))
+        .map(|square| {
+            let (a, b) = coordinates(square);
+            a.abs().max(b.abs())
+        })
+        .filter(|&n| (0.9..=2.0).contains(&n))
+        .fold(f32::INFINITY, f32::min);
+
+    let mut cells: [Option<(usize, f32)>; 9] = [None; 9];
+    if pitch.is_finite() {
+        for (index, square) in squares.iter().enumerate().filter(|(_, square)| similar(square)) {
+            let (a, b) = coordinates(square);
+            let (a, b) = (a / pitch, b / pitch);
+            let (col, row) = (a.round(), b.round());
+            let error = (a - col).hypot(b - row);
+            if col.abs() > 1.0 || row.abs() > 1.0 || error > LATTICE_TOLERANCE {
+                continue;
+            }
+            let cell = (3.0 * (row + 1.0) + col + 1.0) as usize;
+            if cells[cell].is_none_or(|(_, best)| error < best) {
+                cells[cell] = Some((index, error));
+            }
+        }
+    }
+    let found = cells.iter().filter(|cell| cell.is_some()).count();
+    (cells.map(|cell| cell.map(|(index, _)| index)), found)
+}
+
+/// Find the 3x3 sticker grid of a face in a picture
+///
+/// The rows and columns of the grid are the ones closest to the rows and columns of the picture: a face turned
+/// by more than 45 degrees is read turned by a quarter turn.
+pub fn locate_grid(image: &RgbImage) -> Result<GridDetection, GridRejection> {
+    if image.width() < 16 || image.height() < 16 {
+        return Err(GridRejection::TooSmall);
+    }
+    let squares = find_squares(image);
+    if squares.is_empty() {
+        return Err(GridRejection::NoSquares);
+    }
+
+    let (cells, found) = squares
+        .iter()
+        .map(|center| lattice(center, &squares))
+        .max_by_key(|&(_, found)| found)
+        .unwrap();
+    if found < MIN_STICKERS_FOUND {
+        return Err(GridRejection::NoGrid { squares: squares.len(), best: found });
+    }
+
+    // Fit the grid on the centers of the stickers found
+    let grid_point = |cell: usize| (((cell % 3) as f32 + 0.5) / 3.0, ((cell / 3) as f32 + 0.5) / 3.0);
+    let pairs: Vec<(Point, Point)> = (0..9)
+        .filter_map(|cell| cells[cell].map(|index| (grid_point(cell), squares[index].center)))
+        .collect();
+    let homography = Homography::fit(&pairs).ok_or(GridRejection::BadFit)?;
+
+    let center = &squares[cells[4].unwrap()];
+    let tolerance = LATTICE_TOLERANCE * norm(center.u).max(norm(center.v));
+    for &((gx, gy), point) in &pairs {
+        if norm(sub(homography.map(gx, gy), point)) > tolerance {
+            return Err(GridRejection::BadFit);
+        }
+    }
+
+    let mut stickers = [[(0.0, 0.0); 4]; 9];
+    for (cell, sticker) in stickers.iter_mut().enumerate() {
+        let (col, row) = ((cell % 3) as f32, (cell / 3) as f32);
+        *sticker = [(col, row), (col + 1.0, row), (col + 1.0, row + 1.0), (col, row + 1.0)]
+            .map(|(x, y)| homography.map(x / 3.0, y / 3.0));
+    }
+    let grid = GridLocation::new([(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)].map(|(x, y)| homography.map(x, y)));
+
+    Ok(GridDetection { grid, stickers, found: cells.map(|cell| cell.is_some()) })
+}
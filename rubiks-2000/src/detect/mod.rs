@@ -0,0 +1,6 @@
+pub mod calibration;
+pub mod classify;
+pub mod frame;
+pub mod grid;
+pub mod sample;
+pub mod scan;
\ No newline at end of file
@@ -0,0 +1,103 @@
+// Sampling of the sticker colors in the picture of a face.
+//
+// The face is located by the four corners of its 3x3 sticker grid. Each sticker is sampled in a small square
+// around its center, with the median of each channel (more robust than the mean to the reflections and to the
+// black lines between the stickers).
+
+use image::RgbImage;
+
+/// The size of the sampled square, relatively to the size of a sticker
+const SAMPLE_RATIO: f32 = 0.3;
+
+/// An RGB color with floating components between 0 and 255
+pub type Rgb = [f32; 3];
+
+/// The location of the 3x3 sticker grid of a face in a picture
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridLocation {
+    /// The corners of the grid in pixels (x, y): top left, top right, bottom right, bottom left
+    pub corners: [(f32, f32); 4],
+}
+
+impl GridLocation {
+    /// Create a new GridLocation from its corners (top left, top right, bottom right, bottom left)
+    pub fn new(corners: [(f32, f32); 4]) -> Self {
+        Self { corners }
+    }
+
+    /// Create a GridLocation from a square which is aligned with the picture
+    ///
+    /// # Args
+    /// * 'x, y' - the top left corner of the grid, in pixels
+    /// * 'size' - the size of the grid, in pixels
+    pub fn square(x: f32, y: f32, size: f32) -> Self {
+        Self::new([(x, y), (x + size, y), (x + size, y + size), (x, y + size)])
+    }
+
+    /// Return the point at (u, v) in the grid, (0, 0) being the top left corner and (1, 1) the bottom right one
+    pub fn point(&self, u: f32, v: f32) -> (f32, f32) {
+        let [tl, tr, br, bl] = self.corners;
+        let top = (tl.0 + u * (tr.0 - tl.0), tl.1 + u * (tr.1 - tl.1));
+        let bottom = (bl.0 + u * (br.0 - bl.0), bl.1 + u * (br.1 - bl.1));
+        (top.0 + v * (bottom.0 - top.0), top.1 + v * (bottom.1 - top.1))
+    }
+
+    /// Return the center of the sticker at (row, col), in pixels
+    pub fn sticker_center(&self, row: usize, col: usize) -> (f32, f32) {
+        self.point((col as f32 + 0.5) / 3.0, (row as f32 + 0.5) / 3.0)
+    }
+
+    /// Return the approximate size of a sticker, in pixels
+    pub fn sticker_size(&self) -> f32 {
+        let [tl, tr, br, bl] = self.corners;
+        let length = |a: (f32, f32), b: (f32, f32)| ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt();
+        (length(tl, tr) + length(tr, br) + length(br, bl) + length(bl, tl)) / 12.0
+    }
+
+    /// Return true if the whole grid is inside a picture of this size
+    pub fn is_inside(&self, width: u32, height: u32) -> bool {
+        self.corners
+            .iter()
+            .all(|&(x, y)| x >= 0.0 && y >= 0.0 && x <= width as f32 && y <= height as f32)
+    }
+}
+
+/// Return the median of the values
+fn median(values: &mut [f32]) -> f32 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    values[values.len() / 2]
+}
+
+/// Sample the color of the 9 stickers of a face, row by row (in the order of the Facelet enum)
+///
+/// # Args
+/// * 'image' - the picture of the face
+/// * 'grid' - the location of the sticker grid in the picture, it must be inside the picture
+pub fn sample_stickers(image: &RgbImage, grid: &GridLocation) -> [Rgb; 9] {
+    let half = (grid.sticker_size() * SAMPLE_RATIO / 2.0).max(1.0);
+    let mut colors = [[0.0; 3]; 9];
+
+    for (i, color) in colors.iter_mut().enumerate() {
+        let (cx, cy) = grid.sticker_center(i / 3, i % 3);
+        let mut channels = [Vec::new(), Vec::new(), Vec::new()];
+        let x_range = ((cx - half).max(0.0) as u32)..((cx + half) as u32).min(image.width());
+        for x in x_range {
+            let y_range = ((cy - half).max(0.0) as u32)..((cy + half) as u32).min(image.height());
+            for y in y_range {
+                let pixel = image.get_pixel(x, y);
+                for c in 0..3 {
+                    channels[c].push(pixel[c] as f32);
+                }
+            }
+        }
+        for c in 0..3 {
+            if channels[c].is_empty() {
+                // The sampled square is smaller than a pixel
+                let pixel = image.get_pixel((cx as u32).min(image.width() - 1), (cy as u32).min(image.height() - 1));
+                channels[c].push(pixel[c] as f32);
+            }
+            color[c] = median(&mut channels[c]);
+        }
+    }
+    colors
+}
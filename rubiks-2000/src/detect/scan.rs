@@ -0,0 +1,205 @@
+// Guided scan of the six faces.
+//
+// The session asks for the faces one by one (SCAN_ORDER) and checks the center of each captured face. The most
+// common mistake is to hold a face turned by a quarter or a half turn: when the six faces are captured, every
+// combination of rotations of the faces is tried, and the one where the most corners and edges have the colors
+// of a real cubie (CORNER_COLOR / EDGE_COLOR) is used, the faces being turned back before building the FaceCube.
+
+use crate::cube::defs::*;
+use crate::cube::enums::{ Color, CubeError };
+use crate::cube::face_cube::FaceCube;
+use std::fmt;
+
+/// The order in which the faces are asked: around the cube, then the top and the bottom
+pub const SCAN_ORDER: [Color; 6] = [Color::F, Color::R, Color::B, Color::L, Color::U, Color::D];
+
+/// The number of cubies (corners and edges) checked for the orientation of the faces
+const N_CUBIES: usize = N_CORNERS + N_EDGES;
+
+/// The state of a scan session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanState {
+    /// Waiting for the capture of a face
+    Scanning(Color),
+    /// The six faces are captured
+    Complete,
+}
+
+/// The errors of a scan session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanError {
+    /// The center of the captured face is not the color of the face asked
+    WrongCenter { expected: Color, found: Color },
+    /// A face is not captured yet
+    Missing(Color),
+    /// Whatever the rotations of the faces, `valid` cubies at most have the colors of a real cubie:
+    /// some stickers are misread or some faces are swapped
+    Inconsistent { valid: usize },
+    /// The faces are consistent but the cube is not solvable (a twisted corner, a flipped edge...)
+    Illegal(CubeError),
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScanError::WrongCenter { expected, found } => {
+                write!(f, "The center of the face is {} instead of {}", found, expected)
+            }
+            ScanError::Missing(face) => write!(f, "The face {} is not scanned", face),
+            ScanError::Inconsistent { valid } => {
+                write!(f, "Only {} of the {} corners and edges are consistent, scan the faces again", valid, N_CUBIES)
+            }
+            ScanError::Illegal(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ScanError {}
+
+/// The cube built by a scan session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Assembly {
+    pub cube: FaceCube,
+    /// The number of clockwise quarter turns applied to each captured face (indexed by the Color enum),
+    /// 0 for the faces which were captured in the right orientation
+    pub rotations: [u8; 6],
+}
+
+/// Return the orientation in which a face must be held in front of the camera (see the Facelet enum doc comment)
+pub fn orientation_hint(face: Color) -> &'static str {
+    match face {
+        Color::U => "U face in front of the camera, B face on top",
+        Color::D => "D face in front of the camera, F face on top",
+        Color::R => "R face in front of the camera, U face on top",
+        Color::F => "F face in front of the camera, U face on top",
+        Color::L => "L face in front of the camera, U face on top",
+        Color::B => "B face in front of the camera, U face on top",
+    }
+}
+
+/// Return the stickers of a face turned by a clockwise quarter turn
+pub fn rotate_face(stickers: [Color; 9]) -> [Color; 9] {
+    let mut rotated = stickers;
+    for row in 0..3 {
+        for col in 0..3 {
+            rotated[3 * row + col] = stickers[3 * (2 - col) + row];
+        }
+    }
+    rotated
+}
+
+/// A guided scan of the six faces
+#[derive(Debug, Clone, Default)]
+pub struct ScanSession {
+    /// The captured stickers of each face (indexed by the Color enum), row by row
+    faces: [Option<[Color; 9]>; 6],
+}
+
+impl ScanSession {
+    /// Start a new scan session
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the state of the session: the next face to capture in SCAN_ORDER, or Complete
+    pub fn state(&self) -> ScanState {
+        match SCAN_ORDER.iter().find(|&&face| self.faces[face as usize].is_none()) {
+            Some(&face) => ScanState::Scanning(face),
+            None => ScanState::Complete,
+        }
+    }
+
+    /// Return the number of faces captured
+    pub fn captured(&self) -> usize {
+        self.faces.iter().filter(|face| face.is_some()).count()
+    }
+
+    /// Submit the capture of the face asked, checking its center. Returns the new state of the session.
+    ///
+    /// # Args
+    /// * 'stickers' - the colors of the stickers, row by row as seen by the camera
+    pub fn submit(&mut self, stickers: [Color; 9]) -> Result<ScanState, ScanError> {
+        let ScanState::Scanning(expected) = self.state() else {
+            return Ok(ScanState::Complete);
+        };
+        if stickers[4] != expected {
+            return Err(ScanError::WrongCenter { expected, found: stickers[4] });
+        }
+        self.faces[expected as usize] = Some(stickers);
+        Ok(self.state())
+    }
+
+    /// Forget the capture of a face, to scan it again
+    pub fn retake(&mut self, face: Color) {
+        self.faces[face as usize] = None;
+    }
+
+    /// Build the cube from the six faces, turning back the faces which were captured rotated
+    pub fn assemble(&self) -> Result<Assembly, ScanError> {
+        let mut faces = [[Color::U; 9]; 6];
+        for face in SCAN_ORDER {
+            faces[face as usize] = self.faces[face as usize].ok_or(ScanError::Missing(face))?;
+        }
+
+        // The four rotations of each face
+        let mut rotated = [[[Color::U; 9]; 4]; 6];
+        for (face, stickers) in faces.iter().enumerate() {
+            rotated[face][0] = *stickers;
+            for turns in 1..4 {
+                rotated[face][turns] = rotate_face(rotated[face][turns - 1]);
+            }
+        }
+
+        // The best combination: legal first, then the most valid cubies, then the fewest rotated faces
+        let mut best: Option<((bool, usize, usize), Assembly)> = None;
+        for combination in 0..4usize.pow(6) {
+            let mut rotations = [0u8; 6];
+            let mut cube = FaceCube::new();
+            for (face, turns) in rotations.iter_mut().enumerate() {
+                *turns = ((combination >> (2 * face)) & 3) as u8;
+                for (index, &color) in rotated[face][*turns as usize].iter().enumerate() {
+                    cube.set_facelet(9 * face + index, color);
+                }
+            }
+            let valid = valid_cubies(&cube);
+            let legal = valid == N_CUBIES && cube.to_cubie_cube().check().is_ok();
+            let unchanged = rotations.iter().filter(|&&turns| turns == 0).count();
+            let key = (legal, valid, unchanged);
+            if best.as_ref().is_none_or(|(best_key, _)| key > *best_key) {
+                best = Some((key, Assembly { cube, rotations }));
+            }
+        }
+
+        let ((legal, valid, _), assembly) = best.unwrap();
+        if legal {
+            Ok(assembly)
+        } else if valid < N_CUBIES {
+            Err(ScanError::Inconsistent { valid })
+        } else {
+            Err(ScanError::Illegal(assembly.cube.to_cubie_cube().check().unwrap_err()))
+        }
+    }
+}
+
+/// Return the number of corners and edges of the cube which have the colors of a real cubie
+fn valid_cubies(cube: &FaceCube) -> usize {
+    let corners = CORNER_FACELET
+        .iter()
+        .filter(|facelets| {
+            let colors = facelets.map(|facelet| cube.get_facelet(facelet as usize));
+            CORNER_COLOR
+                .iter()
+                .any(|cubie| (0..3).any(|ori| (0..3).all(|k| colors[(k + ori) % 3] == cubie[k])))
+        })
+        .count();
+    let edges = EDGE_FACELET
+        .iter()
+        .filter(|facelets| {
+            let colors = facelets.map(|facelet| cube.get_facelet(facelet as usize));
+            EDGE_COLOR
+                .iter()
+                .any(|cubie| colors == *cubie || colors == [cubie[1], cubie[0]])
+        })
+        .count();
+    corners + edges
+}
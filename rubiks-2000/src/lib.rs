@@ -1,2 +1,5 @@
 pub mod cube;
-pub mod table;
+pub mod detect;
+pub mod render;
+pub mod solution;
+pub mod table;
\ No newline at end of file
@@ -1,6 +1,9 @@
 #![allow(dead_code)] // This macros disable all the "unsed function/variable ..." warning, to simplify clarity during dev
 
 mod cube;
+mod detect;
+mod render;
+mod solution;
 mod table;
 use crate::cube::enums::Move;
 
@@ -0,0 +1,3 @@
+pub mod svg;
+pub mod terminal;
+pub mod theme;
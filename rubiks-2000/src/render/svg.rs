@@ -0,0 +1,256 @@
+// SVG rendering of a FaceCube, used for the pictures of the web site, the scramble sheets and the bug reports.
+//
+// Three views are available:
+// * Net: the 2D unfolded cube, as drawn in the Facelet enum doc comment
+// * Isometric: the U, F and R faces seen from the front-right-up corner
+// * LastLayer: the U face seen from the top, with the top stickers of the four side faces around it
+
+use crate::cube::enums::{ Color, Move };
+use crate::cube::face_cube::FaceCube;
+use crate::cube::notation::algorithm_to_string;
+use crate::render::theme::Theme;
+use std::fmt::Write;
+
+/// The way the cube is drawn
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum View {
+    Net,
+    Isometric,
+    LastLayer,
+}
+
+/// The options of the rendering
+#[derive(Debug, Clone)]
+pub struct SvgOptions {
+    /// The view to draw
+    pub view: View,
+    /// The size of a sticker, in pixels
+    pub sticker_size: f32,
+    /// The colors of the faces (any SVG color), indexed by the Color enum
+    pub colors: [String; 6],
+    /// The color of the masked stickers
+    pub mask_color: String,
+    /// The color of the lines between the stickers
+    pub border_color: String,
+    /// The background color, transparent if None
+    pub background: Option<String>,
+    /// The facelets to draw (in the order of the Facelet enum), the others are greyed out.
+    /// None to draw all of them.
+    pub mask: Option<[bool; 54]>,
+}
+
+impl Default for SvgOptions {
+    /// A net with the usual western color scheme (white up, green front)
+    fn default() -> Self {
+        Self {
+            view: View::Net,
+            sticker_size: 20.0,
+            colors: Theme::WESTERN.faces.map(|color| color.to_string()),
+            mask_color: String::from("#808080"),
+            border_color: String::from("#000000"),
+            background: None,
+            mask: None,
+        }
+    }
+}
+
+impl SvgOptions {
+    /// The default options with the colors of a theme: its face colors, its body color between the stickers
+    /// and its background
+    pub fn from_theme(theme: &Theme) -> Self {
+        Self {
+            colors: theme.faces.map(|color| color.to_string()),
+            border_color: theme.body.to_string(),
+            background: Some(theme.background.to_string()),
+            ..Self::default()
+        }
+    }
+}
+
+/// A sticker to draw: the facelet index and the polygon (in sticker units)
+type Sticker = (usize, Vec<(f32, f32)>);
+
+/// The position of each face in the net, in stickers, indexed by the Color enum
+const NET_ORIGINS: [(f32, f32); 6] = [(3.0, 0.0), (6.0, 3.0), (3.0, 3.0), (3.0, 6.0), (0.0, 3.0), (9.0, 3.0)];
+
+/// The thickness of the side stickers in the last layer view, in stickers
+const SIDE_THICKNESS: f32 = 0.3;
+
+/// The space between the U face and the side stickers in the last layer view, in stickers
+const SIDE_GAP: f32 = 0.1;
+
+/// The stickers of the net view
+fn net_stickers() -> Vec<Sticker> {
+    let mut stickers = Vec::new();
+    for (face, (ox, oy)) in NET_ORIGINS.iter().enumerate() {
+        for i in 0..9 {
+            let x = ox + ((i % 3) as f32);
+            let y = oy + ((i / 3) as f32);
+            stickers.push((9 * face + i, vec![(x, y), (x + 1.0, y), (x + 1.0, y + 1.0), (x, y + 1.0)]));
+        }
+    }
+    stickers
+}
+
+/// Project a 3D point of the cube (x to the right, y up, z to the front, the cube being [0, 3]^3) on the screen
+fn isometric_projection((x, y, z): (f32, f32, f32)) -> (f32, f32) {
+    let cos = (std::f32::consts::PI / 6.0).cos();
+    let sin = 0.5;
+    // Shifted so that the drawing starts at (0, 0)
+    ((x - z) * cos + 3.0 * cos, (x + z) * sin - y + 3.0)
+}
+
+/// The stickers of the isometric view (U, F and R faces)
+fn isometric_stickers() -> Vec<Sticker> {
+    let mut stickers = Vec::new();
+    for i in 0..9 {
+        let (r, c) = ((i / 3) as f32, (i % 3) as f32);
+        // U face: U1 is at the back left corner
+        let u = [(c, 3.0, r), (c + 1.0, 3.0, r), (c + 1.0, 3.0, r + 1.0), (c, 3.0, r + 1.0)];
+        // F face: F1 is at the top left corner
+        let f = [(c, 3.0 - r, 3.0), (c + 1.0, 3.0 - r, 3.0), (c + 1.0, 2.0 - r, 3.0), (c, 2.0 - r, 3.0)];
+        // R face: R1 is at the top front corner
+        let rr = [(3.0, 3.0 - r, 3.0 - c), (3.0, 3.0 - r, 2.0 - c), (3.0, 2.0 - r, 2.0 - c), (3.0, 2.0 - r, 3.0 - c)];
+        for (face, points) in [(Color::U, u), (Color::F, f), (Color::R, rr)] {
+            stickers.push((9 * (face as usize) + i, points.iter().map(|&p| isometric_projection(p)).collect()));
+        }
+    }
+    stickers
+}
+
+/// The stickers of the last layer view (U face and the first row of the side faces)
+fn last_layer_stickers() -> Vec<Sticker> {
+    let mut stickers = Vec::new();
+    let o = SIDE_THICKNESS + SIDE_GAP;
+    let rect = |x: f32, y: f32, w: f32, h: f32| vec![(x, y), (x + w, y), (x + w, y + h), (x, y + h)];
+
+    for i in 0..9 {
+        let (r, c) = ((i / 3) as f32, (i % 3) as f32);
+        stickers.push((9 * (Color::U as usize) + i, rect(o + c, o + r, 1.0, 1.0)));
+    }
+    for i in 0..3 {
+        let k = i as f32;
+        // Seen from the top: B3 B2 B1 from left to right above the U face, F1 F2 F3 under it,
+        // L1 L2 L3 from top to bottom on the left and R3 R2 R1 on the right
+        stickers.push((9 * (Color::B as usize) + 2 - i, rect(o + k, 0.0, 1.0, SIDE_THICKNESS)));
+        stickers.push((9 * (Color::F as usize) + i, rect(o + k, o + 3.0 + SIDE_GAP, 1.0, SIDE_THICKNESS)));
+        stickers.push((9 * (Color::L as usize) + i, rect(0.0, o + k, SIDE_THICKNESS, 1.0)));
+        stickers.push((9 * (Color::R as usize) + 2 - i, rect(o + 3.0 + SIDE_GAP, o + k, SIDE_THICKNESS, 1.0)));
+    }
+    stickers
+}
+
+/// Return the stickers of a view and its size (in stickers)
+fn view_stickers(view: View) -> (Vec<Sticker>, (f32, f32)) {
+    match view {
+        View::Net => (net_stickers(), (12.0, 9.0)),
+        View::Isometric => {
+            let cos = (std::f32::consts::PI / 6.0).cos();
+            (isometric_stickers(), (6.0 * cos, 6.0))
+        }
+        View::LastLayer => {
+            let size = 3.0 + 2.0 * (SIDE_THICKNESS + SIDE_GAP);
+            (last_layer_stickers(), (size, size))
+        }
+    }
+}
+
+/// Write the stickers of the cube as SVG polygons, with an offset in pixels
+fn write_stickers(svg: &mut String, cube: &FaceCube, options: &SvgOptions, (dx, dy): (f32, f32)) {
+    let (stickers, _) = view_stickers(options.view);
+    let s = options.sticker_size;
+
+    for (facelet, polygon) in stickers {
+        let visible = options.mask.is_none_or(|mask| mask[facelet]);
+        let color = if visible {
+            &options.colors[cube.get_facelet(facelet) as usize]
+        } else {
+            &options.mask_color
+        };
+        let points: Vec<String> = polygon
+            .iter()
+            .map(|(x, y)| format!("{:.1},{:.1}", dx + x * s, dy + y * s))
+            .collect();
+        writeln!(
+            svg,
+            "<polygon points=\"{}\" fill=\"{}\" stroke=\"{}\" stroke-width=\"{:.1}\"/>",
+            points.join(" "),
+            color,
+            options.border_color,
+            s / 20.0
+        ).unwrap();
+    }
+}
+
+/// Write the header of the SVG document and its background
+fn write_header(svg: &mut String, (width, height): (f32, f32), options: &SvgOptions) {
+    writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.0}\" height=\"{h:.0}\" viewBox=\"0 0 {w:.0} {h:.0}\">",
+        w = width,
+        h = height
+    ).unwrap();
+    if let Some(background) = &options.background {
+        writeln!(svg, "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>", background).unwrap();
+    }
+}
+
+/// Draw the cube as an SVG document
+///
+/// # Args
+/// * 'cube' - the cube to draw
+/// * 'options' - the view, the colors and the mask to use
+pub fn to_svg(cube: &FaceCube, options: &SvgOptions) -> String {
+    let (_, (w, h)) = view_stickers(options.view);
+    let margin = options.sticker_size / 2.0;
+    let mut svg = String::new();
+
+    write_header(&mut svg, (w * options.sticker_size + 2.0 * margin, h * options.sticker_size + 2.0 * margin), options);
+    write_stickers(&mut svg, cube, options, (margin, margin));
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Draw a step sheet: the cube before the algorithm and after each of its moves, in a grid
+///
+/// # Args
+/// * 'cube' - the state before the first move
+/// * 'moves' - the algorithm to apply
+/// * 'options' - the view, the colors and the mask to use for each state
+/// * 'columns' - the number of states per line
+pub fn step_sheet(cube: &FaceCube, moves: &[Move], options: &SvgOptions, columns: usize) -> String {
+    let (_, (w, h)) = view_stickers(options.view);
+    let s = options.sticker_size;
+    let margin = s / 2.0;
+    let label_height = s;
+    let cell = (w * s + 2.0 * margin, h * s + 2.0 * margin + label_height);
+    let columns = columns.max(1);
+    let rows = (moves.len() + 1).div_ceil(columns);
+    let mut svg = String::new();
+
+    write_header(&mut svg, (cell.0 * (columns.min(moves.len() + 1) as f32), cell.1 * (rows as f32)), options);
+
+    let mut state = *cube;
+    for step in 0..=moves.len() {
+        let label = if step == 0 {
+            String::from("Start")
+        } else {
+            state.apply_move(moves[step - 1]);
+            format!("{}. {}", step, algorithm_to_string(&moves[step - 1..step]))
+        };
+        let x = cell.0 * ((step % columns) as f32);
+        let y = cell.1 * ((step / columns) as f32);
+
+        writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"sans-serif\" font-size=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+            x + cell.0 / 2.0,
+            y + margin + 0.75 * label_height,
+            0.8 * s,
+            label
+        ).unwrap();
+        write_stickers(&mut svg, &state, options, (x + margin, y + margin + label_height));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
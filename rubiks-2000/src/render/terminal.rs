@@ -0,0 +1,123 @@
+// Terminal printing of a cube as a 2D unfolded net (same layout as the Facelet enum doc comment):
+//
+//         ██████
+//         ██████
+//         ██████
+//  ██████ ██████ ██████ ██████
+//  ██████ ██████ ██████ ██████
+//  ██████ ██████ ██████ ██████
+//         ██████
+//         ██████
+//         ██████
+//
+// The Unicode version draws colored blocks with ANSI escape codes, the ASCII version writes the face letters and
+// can be used where the escape codes are not supported (files, logs, test failures).
+
+use crate::cube::cube::Cube;
+use crate::cube::enums::Color;
+use crate::cube::face_cube::FaceCube;
+use crate::cube::formats::FACE_LETTERS;
+use crate::render::theme::{ Rgb, Theme };
+use std::fmt;
+
+/// The ANSI codes of the usual western color scheme (white up, green front), indexed by the Color enum
+const ANSI_COLORS: [&str; 6] = ["\x1b[37m", "\x1b[31m", "\x1b[32m", "\x1b[33m", "\x1b[38;5;208m", "\x1b[34m"];
+
+/// Code to reset the color to the default one
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// The faces of the three bands of the net, from left to right
+const NET_BANDS: [&[Color]; 3] = [&[Color::U], &[Color::L, Color::F, Color::R, Color::B], &[Color::D]];
+
+/// The characters used to draw the net
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Charset {
+    /// Colored blocks, with ANSI escape codes
+    Unicode,
+    /// The face letters, without escape codes
+    Ascii,
+}
+
+/// A net of a cube, to print with Display:
+/// println!("{}", cube.net());
+#[derive(Clone, Copy)]
+pub struct Net {
+    cube: FaceCube,
+    charset: Charset,
+    /// The colors of the faces of a theme, the 16 terminal colors if None
+    colors: Option<[Rgb; 6]>,
+}
+
+impl Net {
+    /// Create a new net of the cube
+    pub fn new(cube: FaceCube, charset: Charset) -> Self {
+        Self { cube, charset, colors: None }
+    }
+
+    /// Draw the stickers with the colors of a theme (with 24-bit ANSI colors)
+    pub fn with_theme(self, theme: &Theme) -> Self {
+        Self { colors: Some(theme.faces), ..self }
+    }
+
+    /// Draw one sticker (2 characters wide)
+    fn sticker(&self, color: Color) -> String {
+        match (self.charset, self.colors) {
+            (Charset::Unicode, Some(colors)) => format!("{}██{}", colors[color as usize].ansi(), ANSI_RESET),
+            (Charset::Unicode, None) => format!("{}██{}", ANSI_COLORS[color as usize], ANSI_RESET),
+            (Charset::Ascii, _) => format!("{} ", FACE_LETTERS[color as usize]),
+        }
+    }
+}
+
+impl fmt::Display for Net {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for faces in NET_BANDS {
+            for row in 0..3 {
+                // Align the U and D faces on the F face
+                let mut line = if faces.len() == 1 { " ".repeat(7) } else { String::new() };
+                for (i, &face) in faces.iter().enumerate() {
+                    if i > 0 {
+                        line.push(' ');
+                    }
+                    for col in 0..3 {
+                        line += &self.sticker(self.cube.get_facelet(9 * (face as usize) + 3 * row + col));
+                    }
+                }
+                writeln!(f, "{}", line.trim_end())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FaceCube {
+    /// Return the net of the cube drawn with colored blocks, to use with Display
+    pub fn net(self) -> Net {
+        Net::new(self, Charset::Unicode)
+    }
+
+    /// Return the net of the cube drawn with the face letters, to use with Display
+    pub fn ascii_net(self) -> Net {
+        Net::new(self, Charset::Ascii)
+    }
+}
+
+impl Cube {
+    /// Return the net of the cube drawn with colored blocks, to use with Display
+    pub fn net(self) -> Net {
+        self.to_facelet_cube().net()
+    }
+
+    /// Return the net of the cube drawn with the face letters, to use with Display
+    pub fn ascii_net(self) -> Net {
+        self.to_facelet_cube().ascii_net()
+    }
+}
+
+/// Show the facelet string and the net of the cube, so that a failing assert_eq! draws the cubes
+impl fmt::Debug for FaceCube {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "FaceCube({})", self.to_string())?;
+        write!(f, "{}", self.ascii_net())
+    }
+}
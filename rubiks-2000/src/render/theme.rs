@@ -0,0 +1,284 @@
+// The colors used to draw the cube, shared by the 3D viewer, the terminal printer and the SVG renderer.
+//
+// A theme file is a small TOML file:
+//
+//     # Rubik's 2000 theme
+//     background = "#000000"
+//     body = "#000000"
+//     gap = 0.2
+//
+//     [faces]
+//     U = "#ffffff"
+//     R = "#b71234"
+//     F = "#009b48"
+//     D = "#ffd500"
+//     L = "#ff5800"
+//     B = "#0046ad"
+//
+// Only this subset of TOML is read: "key = value" lines, the [faces] table and the comments.
+
+use crate::cube::formats::FACE_LETTERS;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// The first line of the theme files
+const THEME_HEADER: &str = "# Rubik's 2000 theme";
+
+/// The names of the built-in themes, see Theme::builtin
+pub const BUILTIN_THEMES: [&str; 5] = ["western", "japanese", "delta", "colorblind", "colorblind-contrast"];
+
+/// A color of a theme, written "#rrggbb" in the theme files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    /// Return the components between 0 and 1, as used by the 3D viewer
+    pub fn to_unit(self) -> (f32, f32, f32) {
+        (self.0 as f32 / 255.0, self.1 as f32 / 255.0, self.2 as f32 / 255.0)
+    }
+
+    /// Return the ANSI escape code which writes the next characters in this color (24-bit colors)
+    pub fn ansi(self) -> String {
+        format!("\x1b[38;2;{};{};{}m", self.0, self.1, self.2)
+    }
+}
+
+impl fmt::Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+impl FromStr for Rgb {
+    type Err = ThemeError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let bad_color = || ThemeError::Parse(format!("bad color \"{}\", expected #rrggbb", text));
+        let hex = text.strip_prefix('#').filter(|hex| hex.len() == 6).ok_or_else(bad_color)?;
+        let component = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| bad_color());
+        Ok(Rgb(component(0)?, component(2)?, component(4)?))
+    }
+}
+
+/// The colors of the cube
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    /// The colors of the faces, indexed by the Color enum (U, R, F, D, L, B)
+    pub faces: [Rgb; 6],
+    /// The color of the plastic between the stickers
+    pub body: Rgb,
+    /// The background of the pictures and of the 3D view
+    pub background: Rgb,
+    /// The gap between two cublets of the 3D view, relative to the size of a cublet
+    pub gap: f32,
+}
+
+/// The reasons why a theme can not be loaded
+#[derive(Debug, Clone, PartialEq)]
+pub enum ThemeError {
+    /// The theme file can not be read or written
+    Io(String),
+    /// The theme file is malformed
+    Parse(String),
+    /// There is no built-in theme with this name
+    Unknown(String),
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeError::Io(message) => write!(f, "Can not access the theme: {}", message),
+            ThemeError::Parse(message) => write!(f, "Bad theme: {}", message),
+            ThemeError::Unknown(name) => {
+                write!(f, "Unknown theme \"{}\" (built-in themes: {})", name, BUILTIN_THEMES.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ThemeError {}
+
+impl Theme {
+    /// The usual western scheme: white up, green front, red right
+    pub const WESTERN: Theme = Theme {
+        faces: [
+            Rgb(0xff, 0xff, 0xff),
+            Rgb(0xb7, 0x12, 0x34),
+            Rgb(0x00, 0x9b, 0x48),
+            Rgb(0xff, 0xd5, 0x00),
+            Rgb(0xff, 0x58, 0x00),
+            Rgb(0x00, 0x46, 0xad),
+        ],
+        body: Rgb(0, 0, 0),
+        background: Rgb(0, 0, 0),
+        gap: 0.2,
+    };
+
+    /// The japanese scheme: the western one with blue and yellow swapped (white opposite blue)
+    pub const JAPANESE: Theme = Theme {
+        faces: [
+            Rgb(0xff, 0xff, 0xff),
+            Rgb(0xb7, 0x12, 0x34),
+            Rgb(0x00, 0x9b, 0x48),
+            Rgb(0x00, 0x46, 0xad),
+            Rgb(0xff, 0x58, 0x00),
+            Rgb(0xff, 0xd5, 0x00),
+        ],
+        ..Theme::WESTERN
+    };
+
+    /// The first colors of the 3D viewer: blue up, white front, orange right
+    pub const DELTA: Theme = Theme {
+        faces: [
+            Rgb(0x00, 0x00, 0xff),
+            Rgb(0xff, 0x80, 0x00),
+            Rgb(0xff, 0xff, 0xff),
+            Rgb(0x00, 0xff, 0x00),
+            Rgb(0xff, 0x00, 0x00),
+            Rgb(0xff, 0xff, 0x00),
+        ],
+        ..Theme::WESTERN
+    };
+
+    /// The western scheme with the Okabe-Ito palette, which stays readable for the common color blindnesses
+    pub const COLORBLIND: Theme = Theme {
+        faces: [
+            Rgb(0xff, 0xff, 0xff),
+            Rgb(0xd5, 0x5e, 0x00),
+            Rgb(0x00, 0x9e, 0x73),
+            Rgb(0xf0, 0xe4, 0x42),
+            Rgb(0xe6, 0x9f, 0x00),
+            Rgb(0x00, 0x72, 0xb2),
+        ],
+        ..Theme::WESTERN
+    };
+
+    /// The western scheme with colors of very different lightness, for the color blindnesses where
+    /// the hues can not be told apart at all
+    pub const COLORBLIND_CONTRAST: Theme = Theme {
+        faces: [
+            Rgb(0xff, 0xff, 0xff),
+            Rgb(0x80, 0x00, 0x00),
+            Rgb(0x33, 0xbb, 0xee),
+            Rgb(0xff, 0xff, 0x00),
+            Rgb(0xee, 0x77, 0x33),
+            Rgb(0x00, 0x00, 0x80),
+        ],
+        body: Rgb(0x40, 0x40, 0x40),
+        ..Theme::WESTERN
+    };
+
+    /// Return a built-in theme from its name (see BUILTIN_THEMES)
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "western" => Some(Theme::WESTERN),
+            "japanese" => Some(Theme::JAPANESE),
+            "delta" => Some(Theme::DELTA),
+            "colorblind" => Some(Theme::COLORBLIND),
+            "colorblind-contrast" => Some(Theme::COLORBLIND_CONTRAST),
+            _ => None,
+        }
+    }
+
+    /// Read a theme: the name of a built-in theme, or else the path of a theme file
+    pub fn find(name_or_path: &str) -> Result<Self, ThemeError> {
+        match Theme::builtin(name_or_path) {
+            Some(theme) => Ok(theme),
+            None if Path::new(name_or_path).exists() => Theme::load(name_or_path),
+            None => Err(ThemeError::Unknown(String::from(name_or_path))),
+        }
+    }
+
+    /// Read a theme file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ThemeError> {
+        std::fs::read_to_string(path).map_err(|error| ThemeError::Io(error.to_string()))?.parse()
+    }
+
+    /// Write the theme to a file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ThemeError> {
+        std::fs::write(path, self.to_string()).map_err(|error| ThemeError::Io(error.to_string()))
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::WESTERN
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", THEME_HEADER)?;
+        writeln!(f, "background = \"{}\"", self.background)?;
+        writeln!(f, "body = \"{}\"", self.body)?;
+        writeln!(f, "gap = {}", self.gap)?;
+        writeln!(f)?;
+        writeln!(f, "[faces]")?;
+        for (letter, color) in FACE_LETTERS.iter().zip(self.faces) {
+            writeln!(f, "{} = \"{}\"", letter, color)?;
+        }
+        Ok(())
+    }
+}
+
+/// Remove the comment at the end of a line (a # which is not in a string)
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Parse a theme file (see the header of this file). The missing keys keep the values of the western theme.
+impl FromStr for Theme {
+    type Err = ThemeError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut theme = Theme::WESTERN;
+        let mut table = String::new();
+
+        for line in text.lines().map(|line| strip_comment(line).trim()).filter(|line| !line.is_empty()) {
+            if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+                table = String::from(name.trim());
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .ok_or_else(|| ThemeError::Parse(format!("expected key = value in \"{}\"", line)))?;
+            let string = || {
+                value
+                    .strip_prefix('"')
+                    .and_then(|value| value.strip_suffix('"'))
+                    .ok_or_else(|| ThemeError::Parse(format!("expected a string for {}", key)))
+            };
+
+            match (table.as_str(), key) {
+                ("", "background") => theme.background = string()?.parse()?,
+                ("", "body") => theme.body = string()?.parse()?,
+                ("", "gap") => {
+                    theme.gap = value
+                        .parse::<f32>()
+                        .ok()
+                        .filter(|gap| *gap >= 0.0)
+                        .ok_or_else(|| ThemeError::Parse(format!("bad gap \"{}\"", value)))?;
+                }
+                ("faces", letter) => {
+                    let face = FACE_LETTERS
+                        .iter()
+                        .position(|l| l.to_string() == letter)
+                        .ok_or_else(|| ThemeError::Parse(format!("unknown face \"{}\"", letter)))?;
+                    theme.faces[face] = string()?.parse()?;
+                }
+                _ => return Err(ThemeError::Parse(format!("unknown key \"{}\"", key))),
+            }
+        }
+        Ok(theme)
+    }
+}
@@ -0,0 +1,71 @@
+use crate::cube::enums::Move;
+use crate::cube::notation::{ htm_length, qtm_length };
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{ de::Error, Deserialize, Deserializer, Serialize };
+
+/// A solution found by one of the solvers, with some metadata about the search
+/// # Format (with the "serde" feature) :
+/// {"solver": "...", "moves": "R U R' U'", "htm": 4, "qtm": 4, "time": {"secs": 0, "nanos": 0}}
+/// When read, the lengths are computed from the moves: "htm" and "qtm" can be omitted, and a file whose lengths
+/// do not match its moves is rejected.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Solution {
+    /// The name of the solver which found the solution
+    pub solver: String,
+    /// The moves to apply to the cube to solve it
+    #[cfg_attr(feature = "serde", serde(with = "crate::cube::serialize::algorithm"))]
+    pub moves: Vec<Move>,
+    /// Length in the half turn metric
+    pub htm: usize,
+    /// Length in the quarter turn metric
+    pub qtm: usize,
+    /// Time taken to find the solution
+    pub time: Duration,
+}
+
+impl Solution {
+    /// Create a new Solution, the lengths are computed from the moves
+    /// # Args :
+    /// * `solver` : The name of the solver.
+    /// * `moves` : The moves of the solution.
+    /// * `time` : The time taken by the solver.
+    pub fn new(solver: &str, moves: Vec<Move>, time: Duration) -> Self {
+        Self {
+            solver: solver.to_string(),
+            htm: htm_length(&moves),
+            qtm: qtm_length(&moves),
+            moves,
+            time,
+        }
+    }
+}
+
+/// The fields of a Solution as written in a file, see Solution
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct SolutionRepr {
+    solver: String,
+    #[serde(with = "crate::cube::serialize::algorithm")]
+    moves: Vec<Move>,
+    htm: Option<usize>,
+    qtm: Option<usize>,
+    time: Duration,
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Solution {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = SolutionRepr::deserialize(deserializer)?;
+        let solution = Solution::new(&repr.solver, repr.moves, repr.time);
+        if repr.htm.is_some_and(|htm| htm != solution.htm) || repr.qtm.is_some_and(|qtm| qtm != solution.qtm) {
+            return Err(D::Error::custom(format!(
+                "The lengths do not match the moves \"{}\" (htm {}, qtm {})",
+                crate::cube::notation::algorithm_to_string(&solution.moves), solution.htm, solution.qtm
+            )));
+        }
+        Ok(solution)
+    }
+}
@@ -1,7 +1,23 @@
 #[cfg(test)]
 mod tests {
-    use crate::cube::{ cube::Cube, defs::*, enums::Move };
+    use crate::cube::{ cube::Cube, defs::*, enums::{ Color, Facelet, Move }, face_cube::FaceCube };
+    use crate::cube::correction::nearest_legal_state;
+    use crate::detect::{ classify::{ detect, FacePicture }, grid::{ locate_grid, GridRejection }, sample::GridLocation };
+    use crate::detect::calibration::{ CalibrationError, CalibrationProfile };
+    use crate::detect::frame::{ write_raw_frame, DirectorySource, FrameSource, RawSource };
+    use crate::detect::scan::{ rotate_face, ScanError, ScanSession, ScanState, SCAN_ORDER };
+    use crate::cube::partial::{ PartialFaceCube, Resolution, MAX_COMPLETIONS };
+    use crate::cube::formats::{ FACE_LETTERS, WESTERN_COLORS };
+    use crate::render::svg::{ step_sheet, to_svg, SvgOptions, View };
+    use crate::render::theme::{ Rgb as ThemeColor, Theme, ThemeError, BUILTIN_THEMES };
+    use crate::cube::notation::{ algorithm_to_string, htm_length, parse_algorithm, qtm_length };
+    use image::{ Rgb, RgbImage };
     use rand::{ thread_rng, Rng };
+    use std::collections::HashSet;
+    use std::time::Duration;
+
+    #[cfg(feature = "serde")]
+    use crate::solution::Solution;
 
     #[test]
     /// Apply the basics move to a solved cube and check the result
@@ -125,4 +141,621 @@ mod tests {
         assert_eq!(Move::R2.move_inv(),Move::R2);
         assert_eq!(Move::R3.move_inv(),Move::R1);
     }
+
+    #[test]
+    /// The corner parity counts the inversions of all the corners (a quarter turn is odd)
+    fn test_corner_parity() {
+        assert_eq!(DEFAULT.corner_parity(), 0);
+        for (index, mv) in ALL_MOVES.iter().enumerate() {
+            let mut cube = DEFAULT;
+            cube.multiply(*mv);
+            let expected = if index % 3 == 1 { 0 } else { 1 };
+            assert_eq!(cube.corner_parity(), expected, "Failed move - {}", index);
+            assert_eq!(cube.corner_parity(), cube.edge_parity(), "Failed move - {}", index);
+        }
+    }
+
+    #[test]
+    /// All the corners are drawn on the facelet cube, and the facelet cube gives back the cubie cube
+    fn test_to_facelet_cube() {
+        assert_eq!(DEFAULT.to_facelet_cube().to_string(), crate::cube::face_cube::FaceCube::new().to_string());
+        for _ in 0..10 {
+            let mut cube = DEFAULT;
+            cube.randomize();
+            assert_eq!(cube.to_facelet_cube().to_cubie_cube(), cube);
+        }
+
+        // The twist of the last corners counts too
+        let twisted = Cube::new(CP_DEFAULT, [0, 0, 0, 0, 0, 0, 0, 1], EP_DEFAULT, EO_DEFAULT);
+        assert!(!twisted.verify());
+    }
+
+    #[test]
+    fn test_symmetries() {
+        for (s, sym) in ALL_SYMMETRIES.iter().enumerate() {
+            let mut cube = *sym;
+            cube.multiply(ALL_SYMMETRIES[SYM_INV[s]]);
+            assert_eq!(cube, DEFAULT, "Failed symmetry inverse - {}", s);
+        }
+
+        let distinct: HashSet<Cube> = ALL_SYMMETRIES.iter().copied().collect();
+        assert_eq!(distinct.len(), N_SYM);
+    }
+
+    #[test]
+    fn test_canonical() {
+        assert_eq!(DEFAULT.canonical(), DEFAULT);
+
+        for _ in 0..10 {
+            let mut cube = DEFAULT;
+            cube.randomize();
+            let canonical = cube.canonical();
+            assert!(canonical <= cube);
+            for sym in 0..N_SYM {
+                let conjugate = cube.conjugate(sym);
+                assert!(conjugate.verify());
+                assert_eq!(conjugate.canonical(), canonical);
+                assert_eq!(
+                    conjugate.to_facelet_cube().canonical(),
+                    cube.to_facelet_cube().canonical()
+                );
+            }
+        }
+
+        // U and R are the same move seen from two different sides
+        let mut positions = HashSet::new();
+        positions.insert(MOVE_U.canonical());
+        positions.insert(MOVE_R.canonical());
+        assert_eq!(positions.len(), 1);
+    }
+
+    #[test]
+    fn test_notation() {
+        for index in 0..N_MOVES {
+            let mv = Move::from(index);
+            assert_eq!(mv.to_notation().parse::<Move>(), Ok(mv));
+            assert_eq!(mv.to_string().parse::<Move>(), Ok(mv));
+        }
+
+        let moves = parse_algorithm("R U2 R' U'  F2'").unwrap();
+        assert_eq!(moves, vec![Move::R1, Move::U2, Move::R3, Move::U3, Move::F2]);
+        assert_eq!(algorithm_to_string(&moves), "R U2 R' U' F2");
+        assert_eq!(htm_length(&moves), 5);
+        assert_eq!(qtm_length(&moves), 7);
+
+        assert!(parse_algorithm("R X").is_err());
+        assert!(parse_algorithm("R U4").is_err());
+        assert!("UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBU".parse::<FaceCube>().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let mut cube = DEFAULT;
+        cube.randomize();
+
+        let json = serde_json::to_string(&cube).unwrap();
+        assert_eq!(serde_json::from_str::<Cube>(&json).unwrap(), cube);
+
+        let face_cube = cube.to_facelet_cube();
+        let json = serde_json::to_string(&face_cube).unwrap();
+        assert_eq!(json, format!("\"{}\"", face_cube.to_string()));
+        assert_eq!(serde_json::from_str::<FaceCube>(&json).unwrap(), face_cube);
+        assert_eq!(serde_json::from_str::<Cube>(&json).unwrap(), cube);
+
+        // A single twisted corner is not a valid state
+        let json = r#"{"cp": ["URF", "UFL", "ULB", "UBR", "DFR", "DLF", "DBL", "DRB"],
+            "co": [1, 0, 0, 0, 0, 0, 0, 0],
+            "ep": ["UR", "UF", "UL", "UB", "DR", "DF", "DL", "DB", "FR", "FL", "BL", "BR"],
+            "eo": [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]}"#;
+        assert!(serde_json::from_str::<Cube>(json).is_err());
+
+        assert_eq!(serde_json::to_string(&Move::R3).unwrap(), "\"R'\"");
+        assert_eq!(serde_json::from_str::<Move>("\"B2\"").unwrap(), Move::B2);
+
+        let solution = Solution::new("test", vec![Move::R1, Move::U2], Duration::from_millis(12));
+        let json = serde_json::to_string(&solution).unwrap();
+        assert!(json.contains("\"moves\":\"R U2\""));
+        assert!(json.contains("\"htm\":2,\"qtm\":3"));
+        assert_eq!(serde_json::from_str::<Solution>(&json).unwrap(), solution);
+
+        // The lengths are computed from the moves
+        let json = r#"{"solver": "test", "moves": "R U", "time": {"secs": 0, "nanos": 0}}"#;
+        let solution = serde_json::from_str::<Solution>(json).unwrap();
+        assert_eq!((solution.htm, solution.qtm), (2, 2));
+        let json = r#"{"solver": "test", "moves": "R U", "htm": 7, "qtm": 2, "time": {"secs": 0, "nanos": 0}}"#;
+        assert!(serde_json::from_str::<Solution>(json).is_err());
+    }
+
+    #[test]
+    fn test_formats() {
+        let solved = FaceCube::new();
+        assert_eq!(
+            solved.to_color_letters(&WESTERN_COLORS),
+            "WWWWWWWWWRRRRRRRRRGGGGGGGGGYYYYYYYYYOOOOOOOOOBBBBBBBBB"
+        );
+        assert_eq!(solved.to_reid(), "UF UR UB UL DF DR DB DL FR FL BR BL UFR URB UBL ULF DRF DFL DLB DBR");
+
+        // The net drawn in the Facelet doc comment is the solved cube
+        let doc_net = "
+              |************|
+              |*U1**U2**U3*|
+              |************|
+              |*U4**U5**U6*|
+              |************|
+              |*U7**U8**U9*|
+              |************|
+ |************|************|************|************|
+ |*L1**L2**L3*|*F1**F2**F3*|*R1**R2**R3*|*B1**B2**B3*|
+ |************|************|************|************|
+ |*L4**L5**L6*|*F4**F5**F6*|*R4**R5**R6*|*B4**B5**B6*|
+ |************|************|************|************|
+ |*L7**L8**L9*|*F7**F8**F9*|*R7**R8**R9*|*B7**B8**B9*|
+ |************|************|************|************|
+              |************|
+              |*D1**D2**D3*|
+              |************|
+              |*D4**D5**D6*|
+              |************|
+              |*D7**D8**D9*|
+              |************|";
+        assert_eq!(FaceCube::from_net(doc_net), Ok(solved));
+
+        let mut cube = DEFAULT;
+        cube.multiply(MOVE_R);
+        let face_cube = cube.to_facelet_cube();
+        assert_eq!(face_cube.to_reid(), "UF FR UB UL DF BR DB DL DR FL UR BL FDR FRU UBL ULF BRD DFL DLB BUR");
+
+        for _ in 0..10 {
+            let face_cube = random_face_cube();
+
+            let letters = face_cube.to_color_letters(&WESTERN_COLORS);
+            assert_eq!(FaceCube::from_color_letters(&letters), Ok(face_cube));
+            assert_eq!(FaceCube::from_color_letters(&letters.to_lowercase()), Ok(face_cube));
+            assert_eq!(FaceCube::from_net(&face_cube.to_net(&WESTERN_COLORS)), Ok(face_cube));
+            assert_eq!(FaceCube::from_net(&face_cube.to_net(&FACE_LETTERS)), Ok(face_cube));
+            assert_eq!(FaceCube::from_reid(&face_cube.to_reid()), Ok(face_cube));
+        }
+
+        assert!(FaceCube::from_color_letters("WWWWWWWWWRRRRRRRRRGGGGGGGGGYYYYYYYYYOOOOOOOOOBBBBBBBBP").is_err());
+        assert!(FaceCube::from_reid("UF UR UB UL DF DR DB DL FR FL BR BL UFR URB UBL ULF DRF DFL DLB").is_err());
+    }
+
+    #[test]
+    fn test_svg() {
+        let mut cube = FaceCube::new();
+        cube.apply_move(Move::R1);
+
+        let mut options = SvgOptions::default();
+        for view in [View::Net, View::Isometric, View::LastLayer] {
+            options.view = view;
+            let svg = to_svg(&cube, &options);
+            assert!(svg.starts_with("<svg"));
+            assert!(svg.ends_with("</svg>\n"));
+            let n_stickers = match view {
+                View::Net => 54,
+                View::Isometric => 27,
+                View::LastLayer => 21,
+            };
+            assert_eq!(svg.matches("<polygon").count(), n_stickers);
+            assert!(!svg.contains(&options.mask_color));
+        }
+
+        // Only the U face is known
+        let mut mask = [false; 54];
+        mask[..9].fill(true);
+        options.mask = Some(mask);
+        options.view = View::Net;
+        let svg = to_svg(&cube, &options);
+        assert_eq!(svg.matches(&options.mask_color).count(), 45);
+
+        options.mask = None;
+        let moves = parse_algorithm("R U R' U'").unwrap();
+        let svg = step_sheet(&FaceCube::new(), &moves, &options, 3);
+        assert_eq!(svg.matches("<polygon").count(), 5 * 54);
+        assert!(svg.contains(">Start</text>"));
+        assert!(svg.contains(">3. R'</text>"));
+    }
+
+    #[test]
+    fn test_terminal_net() {
+        let cube = FaceCube::new();
+        assert_eq!(
+            cube.ascii_net().to_string(),
+            "       U U U\n".repeat(3) +
+                &"L L L  F F F  R R R  B B B\n".repeat(3) +
+                &"       D D D\n".repeat(3)
+        );
+
+        let net = cube.net().to_string();
+        assert_eq!(net.matches("██").count(), 54);
+        assert_eq!(net.matches("\x1b[0m").count(), 54);
+        assert_eq!(net.lines().count(), 9);
+
+        let mut cube = DEFAULT;
+        cube.multiply(MOVE_U);
+        assert_eq!(cube.ascii_net().to_string(), cube.to_facelet_cube().ascii_net().to_string());
+        assert!(format!("{:?}", cube.to_facelet_cube()).contains("F F F  R R R  B B B  L L L\n"));
+    }
+
+    #[test]
+    fn test_partial_resolve() {
+        let face_cube = random_face_cube();
+
+        let partial = PartialFaceCube::from(face_cube);
+        assert_eq!(partial.resolve(), Resolution::Unique(face_cube));
+
+        // The U face was not scanned
+        let mut partial = PartialFaceCube::from(face_cube);
+        for index in (0..9).filter(|&i| i != 4) {
+            partial.set_facelet(index, None);
+        }
+        assert_eq!(partial.unknown_count(), 8);
+        match partial.resolve() {
+            Resolution::Unique(cube) => assert_eq!(cube, face_cube),
+            // The hidden stickers of the U edges can sometimes be exchanged
+            Resolution::Ambiguous { completions, stickers, .. } => {
+                assert!(completions.contains(&face_cube));
+                assert!(stickers.iter().all(|&index| index < 9));
+            }
+            Resolution::Impossible => panic!("The scanned cube has no completion"),
+        }
+
+        // Both stickers of three edges are missing: they can be cycled and flipped
+        let partial: PartialFaceCube = "UUU?U?U?UR?RRRRRRRF?FFFFFFFDDDDDDDDDL?LLLLLLLBBBBBBBBB".parse().unwrap();
+        match partial.resolve() {
+            Resolution::Ambiguous { completions, stickers, truncated } => {
+                // 3 even permutations * 4 flips with an even sum
+                assert_eq!(completions.len(), 12);
+                assert!(completions.contains(&FaceCube::new()));
+                assert!(!truncated);
+                let mut stickers = stickers;
+                stickers.sort();
+                let hidden = [Facelet::U4, Facelet::U6, Facelet::U8, Facelet::R2, Facelet::F2, Facelet::L2];
+                let mut expected: Vec<usize> = hidden
+                    .iter()
+                    .map(|&f| f as usize)
+                    .collect();
+                expected.sort();
+                assert_eq!(stickers, expected);
+            }
+            other => panic!("Expected an ambiguity, got {:?}", other),
+        }
+
+        // Two U stickers on the same edge
+        let mut partial = PartialFaceCube::from(FaceCube::new());
+        partial.set_facelet(Facelet::F2 as usize, Some(Color::U));
+        assert_eq!(partial.resolve(), Resolution::Impossible);
+
+        assert!("UUUU?U?UURR?RRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBB".parse::<PartialFaceCube>().is_err());
+    }
+
+    #[test]
+    fn test_nearest_legal_state() {
+        let face_cube = random_face_cube();
+
+        // A confident reading of the cube
+        let mut probabilities = [[0.02; 6]; 54];
+        for (index, probability) in probabilities.iter_mut().enumerate() {
+            probability[face_cube.get_facelet(index) as usize] = 0.9;
+        }
+        let correction = nearest_legal_state(&probabilities);
+        assert_eq!(correction.cube, face_cube);
+        assert!(correction.changed.is_empty());
+
+        // Two stickers are read with a wrong color, but the right one is close
+        for index in [Facelet::U1 as usize, Facelet::F6 as usize] {
+            let right = face_cube.get_facelet(index) as usize;
+            let wrong = (right + 1) % 6;
+            probabilities[index] = [0.01; 6];
+            probabilities[index][right] = 0.4;
+            probabilities[index][wrong] = 0.55;
+        }
+        let correction = nearest_legal_state(&probabilities);
+        assert_eq!(correction.cube, face_cube);
+        assert_eq!(correction.changed, vec![Facelet::U1 as usize, Facelet::F6 as usize]);
+    }
+
+    #[test]
+    /// The most likely legal cube is found even when it is not among the first MAX_COMPLETIONS completions of the
+    /// uncertain stickers
+    fn test_nearest_legal_state_many_completions() {
+        let mut cube = DEFAULT;
+        for mv in parse_algorithm("R U F' L2 D B' R2 U' F L").unwrap() {
+            cube.multiply(mv.get_cube());
+        }
+        let face_cube = cube.to_facelet_cube();
+
+        // The stickers of 6 edges are uncertain, the right color is only a little more likely
+        let mut probabilities = [[0.02; 6]; 54];
+        for (index, probability) in probabilities.iter_mut().enumerate() {
+            probability[face_cube.get_facelet(index) as usize] = 0.9;
+        }
+        let uncertain: Vec<usize> = EDGE_FACELET[..6].iter().flatten().map(|&f| f as usize).collect();
+        for &index in &uncertain {
+            probabilities[index] = [0.16; 6];
+            probabilities[index][face_cube.get_facelet(index) as usize] = 0.2;
+        }
+        // A sticker of the edge BR is read with the color opposite to its other sticker: no edge has these colors
+        let [other, wrong_index] = EDGE_FACELET[11].map(|f| f as usize);
+        let wrong = (face_cube.get_facelet(other) as usize + 3) % 6;
+        probabilities[wrong_index] = [0.025; 6];
+        probabilities[wrong_index][face_cube.get_facelet(wrong_index) as usize] = 0.3;
+        probabilities[wrong_index][wrong] = 0.6;
+
+        // The right cube is not among the first completions of the uncertain stickers
+        let mut partial = PartialFaceCube::from(face_cube);
+        for &index in uncertain.iter().chain([&wrong_index]) {
+            partial.set_facelet(index, None);
+        }
+        let completions = partial.completions(MAX_COMPLETIONS);
+        assert_eq!(completions.len(), MAX_COMPLETIONS);
+        assert!(!completions.contains(&face_cube));
+
+        let correction = nearest_legal_state(&probabilities);
+        assert_eq!(correction.cube, face_cube);
+        assert_eq!(correction.changed, vec![wrong_index]);
+    }
+
+    /// A random legal facelet cube
+    fn random_face_cube() -> FaceCube {
+        let mut cube = DEFAULT;
+        cube.randomize();
+        cube.to_facelet_cube()
+    }
+
+    /// The color of the sticker `index` on a picture of the cube, with the western colors
+    fn draw_sticker_color(cube: &FaceCube, index: usize) -> [u8; 3] {
+        let ThemeColor(r, g, b) = Theme::WESTERN.faces[cube.get_facelet(index) as usize];
+        [r, g, b]
+    }
+
+    /// Draw the picture of a face: 9 noisy stickers on a black body, the grid at (40, 30) with stickers of 50 pixels
+    fn draw_face(cube: &FaceCube, face: usize) -> RgbImage {
+        let mut rng = thread_rng();
+        let mut picture = RgbImage::from_pixel(240, 220, Rgb([20, 20, 20]));
+        for sticker in 0..9 {
+            let color = draw_sticker_color(cube, 9 * face + sticker);
+            let (x0, y0) = (40 + 50 * (sticker % 3) as u32, 30 + 50 * (sticker / 3) as u32);
+            for x in x0 + 4..x0 + 46 {
+                for y in y0 + 4..y0 + 46 {
+                    let mut noise = |c: u8| (c as i32 + rng.gen_range(-20..=20)).clamp(0, 255) as u8;
+                    picture.put_pixel(x, y, Rgb([noise(color[0]), noise(color[1]), noise(color[2])]));
+                }
+            }
+        }
+        picture
+    }
+
+    #[test]
+    fn test_detect_from_files() {
+        let face_cube = random_face_cube();
+        let grid = GridLocation::square(40.0, 30.0, 150.0);
+
+        // Half of the faces as PNG, the other half as JPEG
+        let directory = std::env::temp_dir().join(format!("rubiks_detect_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let pictures = [0, 1, 2, 3, 4, 5].map(|face| {
+            let extension = if face % 2 == 0 { "png" } else { "jpg" };
+            let path = directory.join(format!("face_{}.{}", face, extension));
+            draw_face(&face_cube, face).save(&path).unwrap();
+            FacePicture::open(&path, grid).unwrap()
+        });
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        let detection = detect(&pictures).unwrap();
+        assert_eq!(detection.cube, face_cube);
+        assert!(detection.uncertain_stickers(0.9).is_empty());
+        assert_eq!(detection.correct().cube, face_cube);
+
+        // The grid must be inside the picture
+        let mut outside = pictures.clone();
+        outside[2].grid = GridLocation::square(100.0, 100.0, 150.0);
+        assert!(detect(&outside).is_err());
+    }
+
+    /// Draw the picture of a face turned by `angle` radians around the point (160, 140), the grid being 150 pixels
+    /// wide, on a noisy gray background
+    fn draw_turned_face(cube: &FaceCube, face: usize, angle: f32) -> RgbImage {
+        let mut rng = thread_rng();
+        let (cos, sin) = (angle.cos(), angle.sin());
+        RgbImage::from_fn(320, 280, |x, y| {
+            let (dx, dy) = (x as f32 - 160.0, y as f32 - 140.0);
+            let u = (dx * cos + dy * sin) / 150.0 + 0.5;
+            let v = (-dx * sin + dy * cos) / 150.0 + 0.5;
+            let color = if !(0.0..1.0).contains(&u) || !(0.0..1.0).contains(&v) {
+                [150, 140, 130]
+            } else if (0.08..0.92).contains(&(3.0 * u).fract()) && (0.08..0.92).contains(&(3.0 * v).fract()) {
+                draw_sticker_color(cube, 9 * face + 3 * (3.0 * v) as usize + (3.0 * u) as usize)
+            } else {
+                [20, 20, 20]
+            };
+            Rgb(color.map(|c| (c as i32 + rng.gen_range(-15..=15)).clamp(0, 255) as u8))
+        })
+    }
+
+    #[test]
+    fn test_locate_grid() {
+        let face_cube = random_face_cube();
+
+        // A face turned by 20 degrees: the corners of the grid are found
+        let angle = 20f32.to_radians();
+        let detection = locate_grid(&draw_turned_face(&face_cube, 0, angle)).unwrap();
+        assert_eq!(detection.found, [true; 9]);
+        let (cos, sin) = (angle.cos(), angle.sin());
+        for (corner, (a, b)) in detection.grid.corners.iter().zip([(-75.0, -75.0), (75.0, -75.0), (75.0, 75.0), (-75.0, 75.0)]) {
+            let expected = (160.0 + a * cos - b * sin, 140.0 + a * sin + b * cos);
+            assert!((corner.0 - expected.0).hypot(corner.1 - expected.1) < 5.0, "{:?} != {:?}", corner, expected);
+        }
+
+        // The whole detection, with faces turned by less than 45 degrees
+        let pictures = [0, 1, 2, 3, 4, 5]
+            .map(|face| FacePicture::locate(draw_turned_face(&face_cube, face, (face as f32 * 15.0 - 40.0).to_radians())).unwrap());
+        assert_eq!(detect(&pictures).unwrap().cube, face_cube);
+
+        // No grid in a picture without a cube
+        let empty = RgbImage::from_fn(320, 280, |_, _| Rgb([150, 140, 130]));
+        assert!(locate_grid(&empty).is_err());
+        assert_eq!(locate_grid(&RgbImage::new(8, 8)), Err(GridRejection::TooSmall));
+    }
+
+    #[test]
+    fn test_frame_sources() {
+        let face_cube = random_face_cube();
+        let frames: Vec<RgbImage> = (0..3).map(|face| draw_face(&face_cube, face)).collect();
+        let interval = Duration::from_millis(40);
+
+        // A directory of pictures, replayed in the order of the names (the other files are ignored)
+        let directory = std::env::temp_dir().join(format!("rubiks_frames_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        for (index, frame) in frames.iter().enumerate() {
+            frame.save(directory.join(format!("frame_{}.png", index))).unwrap();
+        }
+        std::fs::write(directory.join("notes.txt"), "not a frame").unwrap();
+        let mut source = DirectorySource::new(&directory, interval).unwrap();
+        assert_eq!(source.len(), 3);
+        for (index, expected) in frames.iter().enumerate() {
+            let frame = source.next_frame().unwrap().unwrap();
+            assert_eq!(frame.timestamp, interval * index as u32);
+            assert_eq!(&frame.image, expected);
+        }
+        assert!(source.next_frame().unwrap().is_none());
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        // A raw dump
+        let mut dump = Vec::new();
+        for frame in &frames {
+            write_raw_frame(&mut dump, frame).unwrap();
+        }
+        let mut source = RawSource::new(dump.as_slice(), frames[0].width(), frames[0].height(), interval);
+        for (index, expected) in frames.iter().enumerate() {
+            let frame = source.next_frame().unwrap().unwrap();
+            assert_eq!(frame.timestamp, interval * index as u32);
+            assert_eq!(&frame.image, expected);
+        }
+        assert!(source.next_frame().unwrap().is_none());
+
+        // A truncated dump
+        let mut source = RawSource::new(&dump[..dump.len() - 1], frames[0].width(), frames[0].height(), interval);
+        source.next_frame().unwrap();
+        source.next_frame().unwrap();
+        assert!(source.next_frame().is_err());
+    }
+
+    #[test]
+    fn test_scan_session() {
+        let face_cube = random_face_cube();
+        let face = |color: Color| {
+            let mut stickers = [Color::U; 9];
+            for (index, sticker) in stickers.iter_mut().enumerate() {
+                *sticker = face_cube.get_facelet(9 * color as usize + index);
+            }
+            stickers
+        };
+
+        // The F face is captured turned by a quarter turn and the U face by a half turn
+        let mut session = ScanSession::new();
+        assert_eq!(session.state(), ScanState::Scanning(Color::F));
+        assert_eq!(session.assemble(), Err(ScanError::Missing(Color::F)));
+        assert_eq!(
+            session.submit(face(Color::R)),
+            Err(ScanError::WrongCenter { expected: Color::F, found: Color::R })
+        );
+        for color in SCAN_ORDER {
+            let turns = match color {
+                Color::F => 1,
+                Color::U => 2,
+                _ => 0,
+            };
+            let mut stickers = face(color);
+            for _ in 0..turns {
+                stickers = rotate_face(stickers);
+            }
+            session.submit(stickers).unwrap();
+        }
+        assert_eq!(session.state(), ScanState::Complete);
+        let assembly = session.assemble().unwrap();
+        assert_eq!(assembly.cube, face_cube);
+        assert_eq!(assembly.rotations, [2, 0, 3, 0, 0, 0]);
+
+        // A misread corner sticker
+        session.retake(Color::D);
+        assert_eq!(session.state(), ScanState::Scanning(Color::D));
+        let mut stickers = face(Color::D);
+        stickers[0] = if stickers[0] == Color::U { Color::D } else { Color::U };
+        session.submit(stickers).unwrap();
+        assert!(matches!(session.assemble(), Err(ScanError::Inconsistent { .. })));
+    }
+
+    #[test]
+    fn test_calibration() {
+        // The stickers under a warm light, which brings the red closer to the orange and the white to the yellow
+        const LIGHT: [f32; 3] = [0.95, 0.8, 0.55];
+        let mut rng = thread_rng();
+        let mut scan = |cube: &FaceCube| {
+            let mut colors = [[0.0; 3]; 54];
+            for (index, color) in colors.iter_mut().enumerate() {
+                let rgb = draw_sticker_color(cube, index);
+                *color = [0, 1, 2].map(|c| (rgb[c] as f32 * LIGHT[c] + rng.gen_range(-8.0..8.0)).clamp(0.0, 255.0));
+            }
+            colors
+        };
+
+        let profile = CalibrationProfile::calibrate(&scan(&FaceCube::new())).unwrap();
+        // The white face becomes neutral
+        assert!(profile.centers[Color::U as usize][1].abs() < 5.0 && profile.centers[Color::U as usize][2].abs() < 5.0);
+
+        let face_cube = random_face_cube();
+        let colors = scan(&face_cube);
+        assert_eq!(profile.classify(&colors).cube, face_cube);
+        let mut face = [[0.0; 3]; 9];
+        face.copy_from_slice(&colors[18..27]);
+        assert_eq!(profile.classify_face(&face).to_vec(), face_cube.get_facelets()[18..27].to_vec());
+
+        // A profile file
+        let path = std::env::temp_dir().join(format!("rubiks_profile_{}.txt", std::process::id()));
+        profile.save(&path).unwrap();
+        let loaded = CalibrationProfile::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.to_string(), profile.to_string());
+        assert_eq!(loaded.classify(&colors).cube, face_cube);
+        assert!(matches!("white_balance 1 1".parse::<CalibrationProfile>(), Err(CalibrationError::Parse(_))));
+
+        // The calibration needs a solved cube
+        assert!(matches!(CalibrationProfile::calibrate(&colors), Err(CalibrationError::NotSolved(_))));
+    }
+
+    #[test]
+    /// Read and write the theme files, and draw a cube with a theme
+    fn test_theme() {
+        for name in BUILTIN_THEMES {
+            let theme = Theme::builtin(name).unwrap();
+            assert_eq!(theme.to_string().parse::<Theme>(), Ok(theme), "Failed theme - {}", name);
+        }
+        assert_eq!(Theme::find("japanese"), Ok(Theme::JAPANESE));
+        assert!(matches!(Theme::find("no such theme"), Err(ThemeError::Unknown(_))));
+
+        // The missing keys keep the western colors, the comments are ignored
+        let theme: Theme = "# My theme\ngap = 0.1 # thin\n[faces]\nU = \"#123456\"\n".parse().unwrap();
+        assert_eq!(theme.faces[Color::U as usize], ThemeColor(0x12, 0x34, 0x56));
+        assert_eq!(theme.faces[Color::R as usize], Theme::WESTERN.faces[Color::R as usize]);
+        assert_eq!(theme.gap, 0.1);
+        assert!(matches!("[faces]\nU = \"#12345\"".parse::<Theme>(), Err(ThemeError::Parse(_))));
+        assert!(matches!("[faces]\nX = \"#123456\"".parse::<Theme>(), Err(ThemeError::Parse(_))));
+        assert!(matches!("gap = -1".parse::<Theme>(), Err(ThemeError::Parse(_))));
+
+        let path = std::env::temp_dir().join(format!("rubiks_theme_{}.toml", std::process::id()));
+        Theme::COLORBLIND.save(&path).unwrap();
+        let loaded = Theme::find(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, Ok(Theme::COLORBLIND));
+
+        // The renderers use the colors of the theme
+        let cube = FaceCube::new();
+        let svg = to_svg(&cube, &SvgOptions::from_theme(&Theme::JAPANESE));
+        assert!(svg.contains("#0046ad") && svg.contains("fill=\"#000000\""));
+        assert_eq!(SvgOptions::default().colors, SvgOptions::from_theme(&Theme::WESTERN).colors);
+        let net = cube.net().with_theme(&Theme::JAPANESE).to_string();
+        assert!(net.contains(&Theme::JAPANESE.faces[Color::D as usize].ansi()));
+    }
 }